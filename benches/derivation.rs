@@ -0,0 +1,82 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use zip32::{
+    cache::DerivationCache,
+    registered::{cryptovalue_from_subpath, PathElement, SecretKey},
+    ChildIndex,
+};
+
+const CONTEXT: &[u8] = b"Zcash benchmarks";
+const SEED: [u8; 32] = [7; 32];
+
+fn bulk_cryptovalues(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registered-bulk-cryptovalues");
+    for count in [10u32, 100, 1000] {
+        let subpaths: Vec<_> = (0..count)
+            .map(|i| {
+                [
+                    PathElement::new(ChildIndex::hardened(0), b"account"),
+                    PathElement::new(ChildIndex::hardened(i), &[]),
+                ]
+            })
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("uncached", count),
+            &subpaths,
+            |b, subpaths| {
+                b.iter(|| {
+                    for subpath in subpaths {
+                        cryptovalue_from_subpath(CONTEXT, &SEED, 1, subpath).unwrap();
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("cached", count),
+            &subpaths,
+            |b, subpaths| {
+                b.iter(|| {
+                    let mut cache = DerivationCache::new(16);
+                    for subpath in subpaths {
+                        cache.cryptovalue(CONTEXT, &SEED, 1, subpath).unwrap();
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn sibling_cryptovalues(c: &mut Criterion) {
+    let parent = SecretKey::from_subpath(CONTEXT, &SEED, 1, &[]).unwrap();
+    let mut group = c.benchmark_group("registered-sibling-cryptovalues");
+    for count in [8u32, 64, 512] {
+        let children: Vec<_> = (0..count)
+            .map(|i| PathElement::new(ChildIndex::hardened(i), b"sibling"))
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("sequential", count),
+            &children,
+            |b, children| {
+                b.iter(|| {
+                    children
+                        .iter()
+                        .map(|elem| parent.derive_child_cryptovalue(elem.child_index(), elem.tag()))
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("batched", count),
+            &children,
+            |b, children| b.iter(|| parent.derive_child_cryptovalues(children)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bulk_cryptovalues, sibling_cryptovalues);
+criterion_main!(benches);
@@ -0,0 +1,129 @@
+//! Generates ZIP 32 test vectors in the zcash-test-vectors JSON format.
+//!
+//! Usage:
+//!
+//! ```text
+//! cargo run --features test-vectors --example generate_test_vectors -- \
+//!     (seed_fingerprint | arbitrary | registered) < inputs.json
+//! ```
+//!
+//! The inputs are read from standard input as a JSON array of objects, with byte strings
+//! encoded as hex, and the test vectors are written to standard output. Each kind of test
+//! vector takes the following fields:
+//!
+//! - `seed_fingerprint`: `{"seed": "..."}`
+//! - `arbitrary`: `{"context_string": "...", "seed": "...", "path": [2147483649, ...]}`
+//! - `registered`: `{"context_string": "...", "seed": "...", "zip_number": 1,
+//!   "subpath": [[2147483650, "<tag>"], ...]}`
+
+use std::{env, io, process};
+
+use serde_json::Value;
+use zip32::{
+    registered::PathElement,
+    test_vectors::{ArbitraryVectors, RegisteredVectors, SeedFingerprintVectors},
+    ChildIndex,
+};
+
+const SOURCE: &str = "Generated by the zip32 crate's generate_test_vectors example";
+
+fn main() {
+    let kind = env::args().nth(1).unwrap_or_else(|| {
+        fail("usage: generate_test_vectors (seed_fingerprint | arbitrary | registered)")
+    });
+    let inputs: Value = serde_json::from_reader(io::stdin())
+        .unwrap_or_else(|e| fail(&format!("inputs are not valid JSON: {}", e)));
+    let inputs = inputs
+        .as_array()
+        .unwrap_or_else(|| fail("inputs must be a JSON array"));
+
+    let json = match kind.as_str() {
+        "seed_fingerprint" => {
+            let mut vectors = SeedFingerprintVectors::new();
+            for input in inputs {
+                vectors
+                    .add(&bytes(input, "seed"))
+                    .unwrap_or_else(|e| fail(&e.to_string()));
+            }
+            vectors.to_json(SOURCE)
+        }
+        "arbitrary" => {
+            let mut vectors = ArbitraryVectors::new();
+            for input in inputs {
+                let path = array(input, "path")
+                    .iter()
+                    .map(child_index)
+                    .collect::<Vec<_>>();
+                vectors
+                    .add(
+                        &bytes(input, "context_string"),
+                        &bytes(input, "seed"),
+                        &path,
+                    )
+                    .unwrap_or_else(|e| fail(&e.to_string()));
+            }
+            vectors.to_json(SOURCE)
+        }
+        "registered" => {
+            let mut vectors = RegisteredVectors::new();
+            for input in inputs {
+                let zip_number = input["zip_number"]
+                    .as_u64()
+                    .and_then(|n| u16::try_from(n).ok())
+                    .unwrap_or_else(|| fail("zip_number must be an integer less than 2^16"));
+                let subpath = array(input, "subpath")
+                    .iter()
+                    .map(|elem| match elem.as_array().map(Vec::as_slice) {
+                        Some([index, tag]) => (child_index(index), decode_hex(tag)),
+                        _ => fail("subpath elements must be [index, tag] pairs"),
+                    })
+                    .collect::<Vec<_>>();
+                let subpath = subpath
+                    .iter()
+                    .map(|(index, tag)| PathElement::new(*index, tag))
+                    .collect::<Vec<_>>();
+                vectors
+                    .add(
+                        &bytes(input, "context_string"),
+                        &bytes(input, "seed"),
+                        zip_number,
+                        &subpath,
+                    )
+                    .unwrap_or_else(|e| fail(&e.to_string()));
+            }
+            vectors.to_json(SOURCE)
+        }
+        other => fail(&format!("unknown kind of test vector: {}", other)),
+    };
+    print!("{}", json);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
+
+fn array<'a>(input: &'a Value, field: &str) -> &'a Vec<Value> {
+    input[field]
+        .as_array()
+        .unwrap_or_else(|| fail(&format!("{} must be an array", field)))
+}
+
+fn bytes(input: &Value, field: &str) -> Vec<u8> {
+    decode_hex(&input[field])
+}
+
+fn decode_hex(value: &Value) -> Vec<u8> {
+    value
+        .as_str()
+        .and_then(|s| hex::decode(s).ok())
+        .unwrap_or_else(|| fail("byte strings must be hex-encoded"))
+}
+
+fn child_index(value: &Value) -> ChildIndex {
+    value
+        .as_u64()
+        .and_then(|i| u32::try_from(i).ok())
+        .and_then(ChildIndex::from_index)
+        .unwrap_or_else(|| fail("child indices must be hardened 32-bit integers"))
+}
@@ -0,0 +1,53 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zip32::DiversifierIndex;
+
+fuzz_target!(|input: (DiversifierIndex, u128, u64, &str)| {
+    let (di, j, k, s) = input;
+
+    // Every diversifier index is an 88-bit integer.
+    let di_u128 = u128::from(di);
+    assert!(di_u128 < (1 << 88));
+    assert_eq!(DiversifierIndex::try_from(di_u128).ok(), Some(di));
+    assert_eq!(DiversifierIndex::from(*di.as_bytes()), di);
+    assert_eq!(u64::try_from(di).ok(), u64::try_from(di_u128).ok());
+    assert_eq!(u32::try_from(di).ok(), u32::try_from(di_u128).ok());
+
+    // Conversion from a u128 succeeds exactly when it fits in 88 bits.
+    match DiversifierIndex::try_from(j) {
+        Ok(dj) => {
+            assert!(j < (1 << 88));
+            assert_eq!(u128::from(dj), j);
+            assert_eq!(di.cmp(&dj), di_u128.cmp(&j));
+        }
+        Err(_) => assert!(j >= (1 << 88)),
+    }
+
+    let dk = DiversifierIndex::from(k);
+    assert_eq!(u128::from(dk), u128::from(k));
+    assert_eq!(u64::try_from(dk).ok(), Some(k));
+
+    // The decimal, integer hex and little-endian hex encodings round-trip.
+    assert_eq!(di.to_string().parse::<DiversifierIndex>(), Ok(di));
+    assert_eq!(di.to_string(), di_u128.to_string());
+    assert_eq!(DiversifierIndex::from_le_hex(&di.to_le_hex()), Ok(di));
+    assert_eq!(format!("{:x}", di), format!("{:x}", di_u128));
+    assert_eq!(format!("{:#026X}", di), format!("{:#026X}", di_u128));
+
+    // Any string that parses is a valid encoding of the index it parses to.
+    if let Ok(ds) = s.parse::<DiversifierIndex>() {
+        assert_eq!(s.parse::<u128>().ok(), Some(u128::from(ds)));
+        assert!(!s.starts_with('+'));
+    }
+    if let Ok(ds) = DiversifierIndex::from_le_hex(s) {
+        assert_eq!(ds.to_le_hex(), s.to_ascii_lowercase());
+    }
+
+    // Incrementing adds one, unless that would overflow.
+    let mut next = di;
+    match next.increment() {
+        Ok(()) => assert_eq!(u128::from(next), di_u128 + 1),
+        Err(_) => assert_eq!(di_u128, (1 << 88) - 1),
+    }
+});
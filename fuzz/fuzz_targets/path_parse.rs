@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zip32::path::Path;
+
+fuzz_target!(|s: &str| {
+    if let Ok(path) = s.parse::<Path>() {
+        // The canonical form of a path parses to the same path, and is its own
+        // canonical form.
+        let canonical = path.to_string();
+        let reparsed = canonical.parse::<Path>().unwrap();
+        assert_eq!(reparsed, path);
+        assert_eq!(reparsed.to_string(), canonical);
+    }
+});
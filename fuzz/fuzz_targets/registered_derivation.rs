@@ -0,0 +1,60 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zip32::{
+    registered::{cryptovalue_from_subpath, DerivationError, PathElement, SecretKey, ZipRoot},
+    ChildIndex,
+};
+
+/// A context string, seed, ZIP number and subpath. The lengths of the context string and
+/// seed are unconstrained, so that invalid lengths are exercised as well as valid ones.
+type Input = (Vec<u8>, Vec<u8>, u16, Vec<(ChildIndex, Vec<u8>)>);
+
+fuzz_target!(|input: Input| {
+    let (context_string, seed, zip_number, elements) = input;
+    let subpath = elements
+        .iter()
+        .map(|(index, tag)| PathElement::new(*index, tag))
+        .collect::<Vec<_>>();
+
+    let expected_error = if context_string.is_empty() || context_string.len() > 252 {
+        Some(DerivationError::ContextStringInvalid)
+    } else if seed.len() < 32 || seed.len() > 252 {
+        Some(DerivationError::SeedInvalid)
+    } else if subpath.is_empty() {
+        Some(DerivationError::SubpathEmpty)
+    } else {
+        None
+    };
+
+    let cryptovalue = cryptovalue_from_subpath(&context_string, &seed, zip_number, &subpath);
+    match expected_error {
+        Some(e) => assert_eq!(cryptovalue, Err(e)),
+        None => {
+            let cryptovalue = cryptovalue.unwrap();
+            let root = ZipRoot::new(&context_string, &seed, zip_number).unwrap();
+            assert_eq!(root.cryptovalue(&subpath), Ok(cryptovalue));
+
+            // The cryptovalue is derived from the key at the parent subpath, which is the
+            // last key reached when walking that subpath.
+            let (last, parent_path) = subpath.split_last().unwrap();
+            let parent =
+                SecretKey::from_subpath(&context_string, &seed, zip_number, parent_path).unwrap();
+            assert_eq!(
+                parent.derive_child_cryptovalue(last.child_index(), last.tag()),
+                cryptovalue
+            );
+            let (_, walked) =
+                SecretKey::walk_subpath(&context_string, &seed, zip_number, parent_path)
+                    .unwrap()
+                    .last()
+                    .unwrap();
+            assert_eq!(walked.data(), parent.data());
+            assert!(bool::from(parent.verify_child_cryptovalue(
+                last.child_index(),
+                last.tag(),
+                &cryptovalue
+            )));
+        }
+    }
+});
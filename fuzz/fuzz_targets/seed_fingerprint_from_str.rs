@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zip32::fingerprint::SeedFingerprint;
+
+fuzz_target!(|s: &str| {
+    if let Ok(fp) = s.parse::<SeedFingerprint>() {
+        // Bech32m strings are either all-lowercase or all-uppercase, and we always
+        // encode in lowercase.
+        let encoded = fp.to_string();
+        assert_eq!(encoded, s.to_lowercase());
+        assert_eq!(encoded.parse::<SeedFingerprint>().unwrap(), fp);
+        assert_eq!(SeedFingerprint::from_bytes(fp.to_bytes()), fp);
+    }
+});
@@ -21,6 +21,9 @@
 //! [hardened key derivation framework]: crate::hardened_only
 //! [adhockd]: https://zips.z.cash/zip-0032#specification-ad-hoc-key-derivation-deprecated
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 use zcash_spec::PrfExpand;
 
 use crate::{
@@ -28,6 +31,9 @@ use crate::{
     ChainCode, ChildIndex,
 };
 
+#[cfg(feature = "alloc")]
+use crate::hardened_only::BatchContext;
+
 use super::with_ikm;
 
 struct Adhoc;
@@ -37,6 +43,11 @@ impl Context for Adhoc {
     const CKD_DOMAIN: HardenedOnlyCkdDomain = PrfExpand::ADHOC_ZIP32_CHILD;
 }
 
+#[cfg(feature = "alloc")]
+impl BatchContext for Adhoc {
+    const CKD_DOMAIN_SEPARATOR: u8 = 0xab;
+}
+
 /// An ad-hoc extended secret key.
 ///
 /// Defined in [ZIP 32: Ad-hoc key generation (deprecated)][adhockd].
@@ -65,6 +76,28 @@ impl SecretKey {
         xsk
     }
 
+    /// Returns an iterator over the ad-hoc keys at each prefix of the given path, from
+    /// the master key (at the empty prefix) to the key at the full path.
+    ///
+    /// The final key yielded is identical to that returned by [`SecretKey::from_path`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - the context string is empty or longer than 252 bytes.
+    /// - the seed is shorter than 32 bytes or longer than 252 bytes.
+    pub fn walk_path<'a>(
+        context_string: &[u8],
+        seed: &[u8],
+        path: &'a [ChildIndex],
+    ) -> PathKeys<'a> {
+        PathKeys {
+            next: Some(Self::master(context_string, seed)),
+            path,
+            depth: 0,
+        }
+    }
+
     /// Generates the master key of an ad-hoc extended secret key.
     ///
     /// Defined in [ZIP 32: Ad-hoc master key generation (deprecated)][adhocmkg].
@@ -93,6 +126,26 @@ impl SecretKey {
         }
     }
 
+    /// Derives the child keys of a parent key at each of the given indices.
+    ///
+    /// The result is identical to deriving each child in turn with [ZIP 32: Ad-hoc child
+    /// key derivation (deprecated)][adhocckd], but the children are hashed in parallel
+    /// SIMD lanes where the platform supports it.
+    ///
+    /// [adhocckd]: https://zips.z.cash/zip-0032#ad-hoc-child-key-derivation-deprecated
+    #[cfg(feature = "alloc")]
+    pub fn derive_children(&self, indices: &[ChildIndex]) -> Vec<Self> {
+        let children = indices
+            .iter()
+            .map(|index| (*index, &[][..]))
+            .collect::<Vec<_>>();
+        self.inner
+            .derive_children_with_tags(&children)
+            .into_iter()
+            .map(|inner| Self { inner })
+            .collect()
+    }
+
     /// Returns the key material for this key.
     pub fn data(&self) -> &[u8; 32] {
         self.inner.parts().0
@@ -127,6 +180,43 @@ impl SecretKey {
     }
 }
 
+/// An iterator over the ad-hoc keys at each prefix of a derivation path.
+///
+/// Constructed by [`SecretKey::walk_path`].
+pub struct PathKeys<'a> {
+    // The key at `path[..depth]`, if it has not yet been yielded.
+    next: Option<SecretKey>,
+    path: &'a [ChildIndex],
+    depth: usize,
+}
+
+impl<'a> Iterator for PathKeys<'a> {
+    type Item = (&'a [ChildIndex], SecretKey);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let xsk = self.next.take()?;
+        let prefix = &self.path[..self.depth];
+        if let Some(i) = self.path.get(self.depth) {
+            self.next = Some(xsk.derive_child(*i));
+            self.depth += 1;
+        }
+        Some((prefix, xsk))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.next.is_some() {
+            self.path.len() - self.depth + 1
+        } else {
+            0
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PathKeys<'_> {}
+
+impl core::iter::FusedIterator for PathKeys<'_> {}
+
 #[cfg(test)]
 mod tests {
     use alloc::string::ToString;
@@ -137,6 +227,15 @@ mod tests {
 
     use super::{with_ikm, ChildIndex, SecretKey};
 
+    #[cfg(feature = "alloc")]
+    use {
+        crate::{
+            fingerprint::testing::arb_seed, registered::testing::arb_context_string,
+            testing::arb_child_index,
+        },
+        proptest::{collection::vec, prelude::*},
+    };
+
     struct TestVector {
         context_string: &'static [u8],
         seed: [u8; 32],
@@ -324,6 +423,12 @@ mod tests {
         },
     ];
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn batched_derivation_matches_context() {
+        crate::hardened_only::check_ckdh_many::<super::Adhoc>();
+    }
+
     #[test]
     fn test_vectors() {
         let context_string = b"Zcash test vectors";
@@ -359,4 +464,109 @@ mod tests {
             assert_eq!(sk.chain_code().as_bytes(), &tv.c);
         }
     }
+
+    #[test]
+    fn walk_path_yields_every_prefix() {
+        let context_string = b"Zcash test vectors";
+        let seed = TEST_VECTORS[0].seed;
+        let path = [
+            ChildIndex::hardened(1),
+            ChildIndex::hardened(2),
+            ChildIndex::hardened(3),
+        ];
+
+        let walk = SecretKey::walk_path(context_string, &seed, &path);
+        assert_eq!(walk.len(), path.len() + 1);
+
+        let mut depth = 0;
+        for (prefix, sk) in walk {
+            assert_eq!(prefix, &path[..depth]);
+            let expected = SecretKey::from_path(context_string, &seed, prefix);
+            assert_eq!(sk.data(), expected.data());
+            assert_eq!(sk.chain_code(), expected.chain_code());
+            depth += 1;
+        }
+        assert_eq!(depth, path.len() + 1);
+
+        // The test vectors for the path [1', 2', 3'] are reached along the way.
+        let keys = SecretKey::walk_path(context_string, &seed, &path)
+            .map(|(_, sk)| *sk.data())
+            .collect::<alloc::vec::Vec<_>>();
+        for (tv, sk) in TEST_VECTORS[..4].iter().zip(&keys) {
+            assert_eq!(sk, &tv.sk);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn batch_derivation_matches_sequential() {
+        let parent = SecretKey::master(b"Zcash test vectors", &[0; 32]);
+        let indices = (0..20)
+            .map(ChildIndex::hardened)
+            .collect::<alloc::vec::Vec<_>>();
+
+        let children = parent.derive_children(&indices);
+        assert_eq!(children.len(), indices.len());
+        for (index, child) in indices.iter().zip(&children) {
+            let expected = parent.derive_child(*index);
+            assert_eq!(child.inner.parts(), expected.inner.parts());
+        }
+
+        assert!(parent.derive_children(&[]).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    proptest! {
+        #[test]
+        fn paths_compose(
+            context_string in arb_context_string(),
+            seed in arb_seed(),
+            prefix in vec(arb_child_index(), 0..=3),
+            suffix in vec(arb_child_index(), 0..=3),
+        ) {
+            let path = prefix.iter().chain(&suffix).copied().collect::<alloc::vec::Vec<_>>();
+            let expected = SecretKey::from_path(&context_string, &seed, &path);
+
+            // Deriving each child in turn from the master key reaches the key at the path.
+            let folded = path
+                .iter()
+                .fold(SecretKey::master(&context_string, &seed), |sk, i| {
+                    sk.derive_child(*i)
+                });
+            assert_eq!(folded.inner.parts(), expected.inner.parts());
+
+            let mut sk = SecretKey::from_path(&context_string, &seed, &prefix);
+            for i in &suffix {
+                sk = sk.derive_child(*i);
+            }
+            assert_eq!(sk.inner.parts(), expected.inner.parts());
+
+            let (walked_path, walked) = SecretKey::walk_path(&context_string, &seed, &path)
+                .nth(prefix.len())
+                .unwrap();
+            assert_eq!(walked_path, &prefix[..]);
+            assert_eq!(
+                walked.inner.parts(),
+                SecretKey::from_path(&context_string, &seed, &prefix).inner.parts()
+            );
+        }
+
+        #[test]
+        fn derive_children_matches_from_path(
+            context_string in arb_context_string(),
+            seed in arb_seed(),
+            prefix in vec(arb_child_index(), 0..=2),
+            indices in vec(arb_child_index(), 0..=9),
+        ) {
+            let parent = SecretKey::from_path(&context_string, &seed, &prefix);
+            let children = parent.derive_children(&indices);
+            assert_eq!(children.len(), indices.len());
+            for (index, child) in indices.iter().zip(&children) {
+                let mut path = prefix.clone();
+                path.push(*index);
+                let expected = SecretKey::from_path(&context_string, &seed, &path);
+                assert_eq!(child.inner.parts(), expected.inner.parts());
+            }
+        }
+    }
 }
@@ -0,0 +1,333 @@
+//! Caching of intermediate keys for registered key derivation.
+//!
+//! [`registered::SecretKey::from_subpath`] and [`registered::cryptovalue_from_subpath`]
+//! derive every key along the path from the seed, including the master key and the
+//! ZIP-number child. When many keys are derived under a common prefix, most of that work
+//! is repeated. [`DerivationCache`] remembers the most recently used intermediate keys,
+//! so that each derivation only needs to compute the path elements that are not shared
+//! with a cached prefix.
+//!
+//! [`registered::SecretKey::from_subpath`]: crate::registered::SecretKey::from_subpath
+//! [`registered::cryptovalue_from_subpath`]: crate::registered::cryptovalue_from_subpath
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    fingerprint::SeedFingerprint,
+    registered::{check_master_inputs, DerivationError, PathElement, SecretKey},
+    ChildIndex,
+};
+
+/// A cached key, which is zeroized when it is evicted or the cache is dropped.
+struct CachedKey {
+    key: SecretKey,
+    last_used: u64,
+}
+
+impl Drop for CachedKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+/// A bounded least-recently-used cache of intermediate registered keys.
+///
+/// Keys are cached by their full derivation path: the context string, the fingerprint of
+/// the seed, the ZIP number, and the subpath from the ZIP-number child. The root of each
+/// ZIP subtree (`m_{context} / zip_number'`) is cached under the empty subpath.
+///
+/// Cached keys are zeroized when they are evicted, when the cache is cleared, and when
+/// the cache is dropped. Keys returned from the cache are copies, which are wrapped in
+/// [`Zeroizing`] so that they are also zeroized when the caller drops them.
+pub struct DerivationCache {
+    capacity: usize,
+    tick: u64,
+    entries: BTreeMap<Vec<u8>, CachedKey>,
+    // Maps each entry's last-used tick to its path, for finding the eviction candidate.
+    recency: BTreeMap<u64, Vec<u8>>,
+}
+
+impl DerivationCache {
+    /// Constructs an empty cache that will hold at most `capacity` keys.
+    ///
+    /// A cache with zero capacity never stores any keys.
+    pub fn new(capacity: usize) -> Self {
+        DerivationCache {
+            capacity,
+            tick: 0,
+            entries: BTreeMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the maximum number of keys this cache will hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of keys currently held in this cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if this cache holds no keys.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes (and zeroizes) all keys held in this cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// Derives the registered key at the given subpath, reusing and populating cached
+    /// intermediate keys.
+    ///
+    /// The result is identical to that of [`SecretKey::from_subpath`] with the same
+    /// arguments.
+    pub fn secret_key(
+        &mut self,
+        context_string: &[u8],
+        seed: &[u8],
+        zip_number: u16,
+        subpath: &[PathElement<'_>],
+    ) -> Result<Zeroizing<SecretKey>, DerivationError> {
+        check_master_inputs(context_string, seed)?;
+        let seed_fp = SeedFingerprint::from_seed(seed).expect("seed length checked above");
+
+        // Encode the path prefix incrementally, recording where each element ends, so
+        // that we can look up every prefix of the subpath.
+        let mut path = encode_root(context_string, &seed_fp, zip_number);
+        let mut prefix_ends = Vec::with_capacity(subpath.len() + 1);
+        prefix_ends.push(path.len());
+        for elem in subpath {
+            encode_element(&mut path, elem);
+            prefix_ends.push(path.len());
+        }
+
+        // Find the longest cached prefix.
+        let cached = prefix_ends
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(depth, end)| self.get(&path[..*end]).map(|key| (depth, key)));
+
+        let (depth, mut xsk) = match cached {
+            Some(found) => found,
+            None => {
+                let master = Zeroizing::new(SecretKey::master(context_string, seed));
+                let root = Zeroizing::new(
+                    master.derive_child(ChildIndex::hardened(u32::from(zip_number))),
+                );
+                self.insert(&path[..prefix_ends[0]], &root);
+                (0, root)
+            }
+        };
+
+        for (elem, end) in subpath[depth..].iter().zip(&prefix_ends[depth + 1..]) {
+            // Replacing the wrapper drops, and so zeroizes, the parent key.
+            xsk = Zeroizing::new(xsk.derive_child_with_tag(elem.child_index(), elem.tag()));
+            self.insert(&path[..*end], &xsk);
+        }
+
+        Ok(xsk)
+    }
+
+    /// Derives the 64-byte cryptovalue at the given non-empty subpath, reusing and
+    /// populating cached intermediate keys.
+    ///
+    /// The result is identical to that of [`cryptovalue_from_subpath`] with the same
+    /// arguments.
+    ///
+    /// [`cryptovalue_from_subpath`]: crate::registered::cryptovalue_from_subpath
+    pub fn cryptovalue(
+        &mut self,
+        context_string: &[u8],
+        seed: &[u8],
+        zip_number: u16,
+        subpath: &[PathElement<'_>],
+    ) -> Result<[u8; 64], DerivationError> {
+        check_master_inputs(context_string, seed)?;
+        let (last, parent_path) = subpath.split_last().ok_or(DerivationError::SubpathEmpty)?;
+        let parent = self.secret_key(context_string, seed, zip_number, parent_path)?;
+        Ok(parent.derive_child_cryptovalue(last.child_index(), last.tag()))
+    }
+
+    fn get(&mut self, path: &[u8]) -> Option<Zeroizing<SecretKey>> {
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self.entries.get_mut(path)?;
+        let path = self
+            .recency
+            .remove(&entry.last_used)
+            .expect("every entry has a recency record");
+        self.recency.insert(tick, path);
+        entry.last_used = tick;
+        Some(Zeroizing::new(entry.key.clone()))
+    }
+
+    fn insert(&mut self, path: &[u8], key: &SecretKey) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(path) {
+            let lru_tick = *self
+                .recency
+                .keys()
+                .next()
+                .expect("cache is non-empty at capacity");
+            let lru_path = self.recency.remove(&lru_tick).expect("key was just found");
+            // Dropping the entry zeroizes the evicted key.
+            self.entries.remove(&lru_path);
+        }
+
+        self.tick += 1;
+        let previous = self.entries.insert(
+            path.to_vec(),
+            CachedKey {
+                key: key.clone(),
+                last_used: self.tick,
+            },
+        );
+        if let Some(previous) = previous {
+            self.recency.remove(&previous.last_used);
+        }
+        self.recency.insert(self.tick, path.to_vec());
+    }
+}
+
+/// Encodes the root of a ZIP subtree as a cache key.
+fn encode_root(context_string: &[u8], seed_fp: &SeedFingerprint, zip_number: u16) -> Vec<u8> {
+    let mut path = Vec::with_capacity(1 + context_string.len() + 32 + 2);
+    path.push(u8::try_from(context_string.len()).expect("context string length checked"));
+    path.extend_from_slice(context_string);
+    path.extend_from_slice(&seed_fp.to_bytes());
+    path.extend_from_slice(&zip_number.to_le_bytes());
+    path
+}
+
+/// Appends an unambiguous encoding of a path element to a cache key.
+fn encode_element(path: &mut Vec<u8>, elem: &PathElement<'_>) {
+    path.extend_from_slice(&elem.child_index().index().to_le_bytes());
+    path.extend_from_slice(&(elem.tag().len() as u64).to_le_bytes());
+    path.extend_from_slice(elem.tag());
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::DerivationCache;
+    use crate::{
+        registered::{cryptovalue_from_subpath, DerivationError, PathElement, SecretKey},
+        ChildIndex,
+    };
+
+    const CONTEXT: &[u8] = b"Zcash test vectors";
+    const SEED: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d,
+        0x1e, 0x1f,
+    ];
+
+    fn subpath(indices: &[u32]) -> Vec<PathElement<'static>> {
+        indices
+            .iter()
+            .map(|i| PathElement::new(ChildIndex::hardened(*i), b"tag"))
+            .collect()
+    }
+
+    #[test]
+    fn cached_matches_uncached() {
+        let mut cache = DerivationCache::new(16);
+        let paths: [&[u32]; 6] = [&[], &[1], &[1, 2], &[1, 3], &[1, 2, 4], &[5]];
+        for indices in paths {
+            let subpath = subpath(indices);
+            let expected = SecretKey::from_subpath(CONTEXT, &SEED, 1, &subpath).unwrap();
+            // Derive twice, so that the second derivation is served from the cache.
+            for _ in 0..2 {
+                let actual = cache.secret_key(CONTEXT, &SEED, 1, &subpath).unwrap();
+                assert_eq!(actual.data(), expected.data());
+                assert_eq!(actual.chain_code(), expected.chain_code());
+            }
+
+            if !subpath.is_empty() {
+                assert_eq!(
+                    cache.cryptovalue(CONTEXT, &SEED, 1, &subpath).unwrap(),
+                    cryptovalue_from_subpath(CONTEXT, &SEED, 1, &subpath).unwrap(),
+                );
+            }
+        }
+        // The ZIP 1 root, [1], [1, 2], [1, 3], [1, 2, 4] and [5].
+        assert_eq!(cache.len(), 6);
+    }
+
+    #[test]
+    fn distinct_inputs_are_not_confused() {
+        let mut cache = DerivationCache::new(16);
+        let path = subpath(&[1]);
+        let a = cache.secret_key(CONTEXT, &SEED, 1, &path).unwrap();
+        let b = cache.secret_key(CONTEXT, &SEED, 2, &path).unwrap();
+        let c = cache.secret_key(b"other context", &SEED, 1, &path).unwrap();
+        let d = cache.secret_key(CONTEXT, &[1; 32], 1, &path).unwrap();
+        let e = cache
+            .secret_key(
+                CONTEXT,
+                &SEED,
+                1,
+                &[PathElement::new(ChildIndex::hardened(1), b"other tag")],
+            )
+            .unwrap();
+        for other in [&b, &c, &d, &e] {
+            assert_ne!(a.data(), other.data());
+        }
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let mut cache = DerivationCache::new(2);
+        cache.secret_key(CONTEXT, &SEED, 1, &[]).unwrap();
+        cache.secret_key(CONTEXT, &SEED, 2, &[]).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // Touch ZIP 1's root so that ZIP 2's root is the least recently used.
+        cache.secret_key(CONTEXT, &SEED, 1, &[]).unwrap();
+        cache.secret_key(CONTEXT, &SEED, 3, &[]).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        let expected = SecretKey::from_subpath(CONTEXT, &SEED, 2, &[]).unwrap();
+        let actual = cache.secret_key(CONTEXT, &SEED, 2, &[]).unwrap();
+        assert_eq!(actual.data(), expected.data());
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let mut cache = DerivationCache::new(0);
+        cache
+            .secret_key(CONTEXT, &SEED, 1, &subpath(&[1, 2]))
+            .unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let mut cache = DerivationCache::new(2);
+        assert_eq!(
+            cache.secret_key(&[], &SEED, 1, &[]).err(),
+            Some(DerivationError::ContextStringInvalid)
+        );
+        assert_eq!(
+            cache.secret_key(CONTEXT, &[0; 31], 1, &[]).err(),
+            Some(DerivationError::SeedInvalid)
+        );
+        assert_eq!(
+            cache.cryptovalue(CONTEXT, &SEED, 1, &[]),
+            Err(DerivationError::SubpathEmpty)
+        );
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+//! Account discovery when restoring a wallet from a seed.
+//!
+//! A wallet restored from a seed does not know how many accounts were previously in use.
+//! Following the account discovery procedure of [BIP 44], accounts are probed in order
+//! starting from [`AccountId::ZERO`], and discovery stops once a run of consecutive unused
+//! accounts (the "gap") has been observed.
+//!
+//! This crate cannot itself determine whether an account has been used, since that
+//! requires deriving pool-specific keys and scanning the chain. Callers instead provide
+//! an [`AccountUsageOracle`] that answers this question however the application likes.
+//!
+//! [BIP 44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki#account-discovery
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{fingerprint::SeedFingerprint, AccountId};
+
+/// A source of truth for whether an account has been used.
+pub trait AccountUsageOracle {
+    /// The error type returned when the oracle cannot answer a query.
+    type Error;
+
+    /// Returns whether the account at `m / purpose' / coin_type' / account'` (for every
+    /// purpose relevant to the application) derived from `seed` has any history.
+    fn was_used(
+        &mut self,
+        seed: &[u8],
+        coin_type: u32,
+        account: AccountId,
+    ) -> Result<bool, Self::Error>;
+}
+
+/// The accounts found by [`discover_accounts`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredAccounts {
+    seed_fingerprint: SeedFingerprint,
+    accounts: Vec<AccountId>,
+}
+
+impl DiscoveredAccounts {
+    /// Returns the fingerprint of the seed from which the accounts were discovered.
+    pub fn seed_fingerprint(&self) -> &SeedFingerprint {
+        &self.seed_fingerprint
+    }
+
+    /// Returns the accounts that were found to have been used, in ascending order.
+    pub fn accounts(&self) -> &[AccountId] {
+        &self.accounts
+    }
+
+    /// Consumes this value, returning the seed fingerprint and the used accounts.
+    pub fn into_parts(self) -> (SeedFingerprint, Vec<AccountId>) {
+        (self.seed_fingerprint, self.accounts)
+    }
+}
+
+/// Errors that can occur during account discovery.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiscoveryError<E> {
+    /// The provided seed data was invalid. A seed must be between 32 and 252 bytes in
+    /// length, inclusive.
+    SeedInvalid,
+    /// The provided coin type was not less than 2^31.
+    CoinTypeInvalid,
+    /// The oracle returned an error.
+    Oracle(E),
+}
+
+impl<E: fmt::Display> fmt::Display for DiscoveryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscoveryError::SeedInvalid => {
+                write!(f, "Seed must be between 32 and 252 bytes, inclusive.")
+            }
+            DiscoveryError::CoinTypeInvalid => write!(f, "Coin type must be less than 2^31."),
+            DiscoveryError::Oracle(e) => write!(f, "Account usage oracle failed: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for DiscoveryError<E> {}
+
+/// Discovers the accounts that have been used under the given seed and coin type.
+///
+/// Accounts are probed in order starting from [`AccountId::ZERO`]. Discovery stops once
+/// `gap` consecutive accounts have been reported as unused by the oracle, or once
+/// [`AccountId::MAX`] has been probed. A `gap` of 1 matches the behaviour specified in
+/// [BIP 44]; a `gap` of 0 probes no accounts.
+///
+/// [BIP 44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki#account-discovery
+pub fn discover_accounts<O: AccountUsageOracle>(
+    seed: &[u8],
+    coin_type: u32,
+    gap: u32,
+    oracle: &mut O,
+) -> Result<DiscoveredAccounts, DiscoveryError<O::Error>> {
+    let seed_fingerprint = SeedFingerprint::from_seed(seed).ok_or(DiscoveryError::SeedInvalid)?;
+    if coin_type >= (1 << 31) {
+        return Err(DiscoveryError::CoinTypeInvalid);
+    }
+
+    let mut accounts = Vec::new();
+    let mut unused_run = 0;
+    let mut next = Some(AccountId::ZERO);
+    while let Some(account) = next {
+        if unused_run >= gap {
+            break;
+        }
+        if oracle
+            .was_used(seed, coin_type, account)
+            .map_err(DiscoveryError::Oracle)?
+        {
+            accounts.push(account);
+            unused_run = 0;
+        } else {
+            unused_run += 1;
+        }
+        next = account.next();
+    }
+
+    Ok(DiscoveredAccounts {
+        seed_fingerprint,
+        accounts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeSet, vec::Vec};
+
+    use super::{discover_accounts, AccountUsageOracle, DiscoveryError};
+    use crate::{fingerprint::SeedFingerprint, AccountId};
+
+    const SEED: [u8; 32] = [7; 32];
+
+    struct MockOracle {
+        used: BTreeSet<AccountId>,
+        probed: Vec<AccountId>,
+        fail_at: Option<AccountId>,
+    }
+
+    impl MockOracle {
+        fn new(used: &[u32]) -> Self {
+            MockOracle {
+                used: used.iter().map(|i| AccountId::const_from_u32(*i)).collect(),
+                probed: Vec::new(),
+                fail_at: None,
+            }
+        }
+    }
+
+    impl AccountUsageOracle for MockOracle {
+        type Error = &'static str;
+
+        fn was_used(
+            &mut self,
+            seed: &[u8],
+            coin_type: u32,
+            account: AccountId,
+        ) -> Result<bool, Self::Error> {
+            assert_eq!(seed, &SEED[..]);
+            assert_eq!(coin_type, 133);
+            self.probed.push(account);
+            if self.fail_at == Some(account) {
+                Err("unavailable")
+            } else {
+                Ok(self.used.contains(&account))
+            }
+        }
+    }
+
+    fn ids(ids: &[u32]) -> Vec<AccountId> {
+        ids.iter().map(|i| AccountId::const_from_u32(*i)).collect()
+    }
+
+    #[test]
+    fn discovery_stops_after_gap() {
+        let mut oracle = MockOracle::new(&[0, 1, 3, 7]);
+        let found = discover_accounts(&SEED, 133, 2, &mut oracle).unwrap();
+        assert_eq!(
+            found.seed_fingerprint(),
+            &SeedFingerprint::from_seed(&SEED).unwrap()
+        );
+        assert_eq!(found.accounts(), &ids(&[0, 1, 3])[..]);
+        assert_eq!(oracle.probed, ids(&[0, 1, 2, 3, 4, 5]));
+
+        let mut oracle = MockOracle::new(&[0, 1, 3, 7]);
+        let found = discover_accounts(&SEED, 133, 4, &mut oracle).unwrap();
+        assert_eq!(found.accounts(), &ids(&[0, 1, 3, 7])[..]);
+        assert_eq!(oracle.probed.len(), 12);
+    }
+
+    #[test]
+    fn discovery_bip44_gap() {
+        let mut oracle = MockOracle::new(&[1]);
+        let found = discover_accounts(&SEED, 133, 1, &mut oracle).unwrap();
+        assert!(found.accounts().is_empty());
+        assert_eq!(oracle.probed, ids(&[0]));
+
+        let mut oracle = MockOracle::new(&[0]);
+        let found = discover_accounts(&SEED, 133, 0, &mut oracle).unwrap();
+        assert!(found.accounts().is_empty());
+        assert!(oracle.probed.is_empty());
+    }
+
+    #[test]
+    fn discovery_errors() {
+        let mut oracle = MockOracle::new(&[]);
+        assert_eq!(
+            discover_accounts(&[0; 31], 133, 1, &mut oracle),
+            Err(DiscoveryError::SeedInvalid)
+        );
+        assert_eq!(
+            discover_accounts(&SEED, 1 << 31, 1, &mut oracle),
+            Err(DiscoveryError::CoinTypeInvalid)
+        );
+        assert!(oracle.probed.is_empty());
+
+        let mut oracle = MockOracle::new(&[0, 1]);
+        oracle.fail_at = Some(AccountId::const_from_u32(1));
+        assert_eq!(
+            discover_accounts(&SEED, 133, 1, &mut oracle),
+            Err(DiscoveryError::Oracle("unavailable"))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Expansion of registered cryptovalues into longer key material.
+//!
+//! [`registered::SecretKey::derive_child_cryptovalue`] produces exactly 64 bytes. An
+//! application protocol that needs more key material, or several independent sub-keys,
+//! can wrap the cryptovalue in a [`LeafKey`] and expand it instead of building its own
+//! key derivation function on top.
+//!
+//! Expansion treats the cryptovalue `I` as a registered extended key, with
+//! `sk = I[..32]` and `c = I[32..]`, and outputs the full-width cryptovalues of its
+//! children. Block `counter` of the output in domain `domain` is
+//!
+//! ```text
+//! PRF^expand_c([0xAC] || sk || I2LEOSP_32(2^31 + counter) || [0x01] || [domain])
+//! ```
+//!
+//! which is [ZIP 32: Full-width child cryptovalue derivation][fwccd] at index
+//! `counter'` with the one-byte tag `[domain]`. `PRF^expand` is therefore used exactly
+//! as specified, with a 32-byte key, through the `zcash_spec` `PrfExpand` domain for
+//! registered child derivation. `zcash_spec` does not allow new `PrfExpand` domains to
+//! be defined outside it, so the application's domain is carried in the tag.
+//!
+//! The `domain` byte is chosen by the application protocol; distinct sub-keys MUST use
+//! distinct domain bytes. This construction is not part of ZIP 32, so the ZIP
+//! documenting the application protocol SHOULD specify the domain bytes it uses and the
+//! length of the output for each of them.
+//!
+//! Output expanded in the same domain with different lengths is not independent: the
+//! shorter output is a prefix of the longer one.
+//!
+//! [`registered::SecretKey::derive_child_cryptovalue`]: crate::registered::SecretKey::derive_child_cryptovalue
+//! [fwccd]: https://zips.z.cash/zip-0032#full-width-child-cryptovalue-derivation
+
+use core::fmt;
+
+use subtle::{Choice, ConstantTimeEq};
+use zcash_spec::PrfExpand;
+use zeroize::Zeroize;
+
+/// A 64-byte registered cryptovalue, used as the key for expansion into sub-keys.
+///
+/// The cryptovalue is zeroized when this value is dropped.
+#[derive(Clone)]
+pub struct LeafKey([u8; 64]);
+
+impl fmt::Debug for LeafKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The key material is not rendered.
+        f.debug_struct("LeafKey").finish_non_exhaustive()
+    }
+}
+
+impl ConstantTimeEq for LeafKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl Drop for LeafKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl LeafKey {
+    /// Wraps a cryptovalue obtained from registered key derivation.
+    pub fn from_cryptovalue(cryptovalue: [u8; 64]) -> Self {
+        LeafKey(cryptovalue)
+    }
+
+    /// Returns the cryptovalue wrapped by this key.
+    pub fn cryptovalue(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    /// Fills `out` with key material expanded from this key in the given domain.
+    ///
+    /// `out` may be of any length up to `2^31` blocks of 64 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is longer than `2^31` blocks of 64 bytes (128 GiB), because the
+    /// block counter would leave the range of hardened child indices.
+    pub fn expand(&self, domain: u8, out: &mut [u8]) {
+        let (sk, chain_code) = self.0.split_at(32);
+        let sk: &[u8; 32] = sk.try_into().expect("32 bytes");
+
+        for (counter, chunk) in out.chunks_mut(64).enumerate() {
+            let index = u32::try_from(counter)
+                .ok()
+                .filter(|counter| counter >> 31 == 0)
+                .expect("output is at most 2^31 blocks")
+                | (1 << 31);
+            let mut block = PrfExpand::REGISTERED_ZIP32_CHILD.with(
+                chain_code,
+                sk,
+                &index.to_le_bytes(),
+                &[1],
+                &[domain],
+            );
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            block.zeroize();
+        }
+    }
+
+    /// Expands a typed sub-key in the given domain.
+    ///
+    /// Each sub-key type documents how many bytes it expands, and how it interprets
+    /// them.
+    pub fn sub_key<K: SubKey>(&self, domain: u8) -> K {
+        K::expand_from(self, domain)
+    }
+}
+
+/// A type of key that can be expanded from a [`LeafKey`], using [`LeafKey::sub_key`].
+///
+/// This is implemented for the key types in [`crate::usage`].
+pub trait SubKey: Sized {
+    /// Expands a key of this type from `leaf` in the given domain.
+    fn expand_from(leaf: &LeafKey, domain: u8) -> Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LeafKey;
+    use crate::{registered::SecretKey, usage::SymmetricKey, ChainCode, ChildIndex};
+
+    // The full-width cryptovalue from the last registered key derivation test vector. The
+    // expected outputs below are generated by
+    // test-vectors/generators/registered_expand.py.
+    const CRYPTOVALUE: [u8; 64] = [
+        0x7f, 0x85, 0x3e, 0xef, 0x00, 0x1b, 0x1b, 0xc5, 0xa1, 0xa5, 0xe6, 0x7f, 0x5d, 0xfd, 0x0e,
+        0x90, 0x42, 0x75, 0x96, 0xd4, 0x84, 0x2f, 0x5b, 0x10, 0xa1, 0x11, 0xe9, 0x7c, 0x40, 0x73,
+        0x20, 0x3c, 0xed, 0xf6, 0xb8, 0x0a, 0x85, 0x14, 0x5e, 0x50, 0x61, 0xac, 0xd2, 0x9b, 0xc5,
+        0xa4, 0xe3, 0x49, 0xb1, 0x4f, 0x85, 0x57, 0xa7, 0x03, 0x3e, 0x23, 0xb0, 0x66, 0xb7, 0xce,
+        0x24, 0x09, 0xd9, 0x73,
+    ];
+
+    #[test]
+    fn expand_vectors() {
+        let leaf = LeafKey::from_cryptovalue(CRYPTOVALUE);
+
+        let mut out = [0; 96];
+        leaf.expand(1, &mut out);
+        assert_eq!(
+            out[..],
+            [
+                0xcc, 0x5e, 0xa8, 0xa9, 0x69, 0x4d, 0xd4, 0x56, 0x91, 0xee, 0x28, 0x51, 0xe8, 0x64,
+                0xc9, 0xe5, 0xaa, 0xf5, 0xeb, 0x65, 0xe5, 0xfb, 0xf5, 0x6d, 0xb1, 0x83, 0xa3, 0xc4,
+                0xb4, 0x7c, 0x5d, 0xb5, 0x52, 0xe8, 0x43, 0xf0, 0x73, 0xea, 0x22, 0xae, 0xf4, 0xc0,
+                0x9d, 0xe7, 0x60, 0x24, 0xd7, 0x33, 0x1b, 0x32, 0x97, 0x1d, 0x7c, 0xca, 0xbc, 0xb1,
+                0xcb, 0x04, 0x2b, 0xcf, 0x18, 0x52, 0x00, 0x7f, 0x73, 0xcb, 0x0f, 0x1c, 0xbb, 0x7b,
+                0x66, 0x2a, 0x81, 0x51, 0x57, 0x0c, 0x00, 0xe9, 0x61, 0x6b, 0x53, 0x0d, 0x5e, 0xc0,
+                0x19, 0xa9, 0x7e, 0x1b, 0x54, 0x58, 0x8d, 0x83, 0xa4, 0x1c, 0xe7, 0x85,
+            ][..]
+        );
+
+        let key = leaf.sub_key::<SymmetricKey>(2);
+        assert_eq!(
+            key.key(),
+            &[
+                0x30, 0xcc, 0x14, 0xde, 0xba, 0x60, 0xb8, 0xd3, 0xdd, 0x6c, 0xa2, 0x42, 0xa8, 0xa9,
+                0x1e, 0xa1, 0x40, 0x96, 0x2f, 0x40, 0x2d, 0x90, 0x67, 0x3f, 0x1d, 0xa5, 0x6f, 0x4a,
+                0x44, 0xc6, 0x0a, 0xe9,
+            ]
+        );
+        assert_eq!(
+            key.nonce_seed(),
+            &[
+                0x65, 0x83, 0x32, 0x70, 0x00, 0x99, 0x64, 0xdd, 0xaa, 0xf7, 0x7d, 0x8d, 0x34, 0xe6,
+                0x79, 0x5a, 0xdd, 0xf9, 0xa9, 0x62, 0x48, 0xb8, 0x85, 0x37, 0x63, 0x7f, 0xfb, 0x53,
+                0x46, 0x5f, 0x63, 0xad,
+            ]
+        );
+    }
+
+    #[test]
+    fn expansion_is_child_cryptovalue_derivation() {
+        let leaf = LeafKey::from_cryptovalue(CRYPTOVALUE);
+        let xsk = SecretKey::from_parts(
+            CRYPTOVALUE[..32].try_into().unwrap(),
+            ChainCode::new(CRYPTOVALUE[32..].try_into().unwrap()),
+        );
+
+        let mut out = [0; 130];
+        leaf.expand(7, &mut out);
+        for (counter, block) in out.chunks(64).enumerate() {
+            let child = xsk.derive_child_cryptovalue(ChildIndex::hardened(counter as u32), &[7]);
+            assert_eq!(block, &child[..block.len()]);
+        }
+    }
+
+    #[test]
+    fn sub_keys_match_expansion() {
+        let leaf = LeafKey::from_cryptovalue(CRYPTOVALUE);
+        let mut out = [0; 130];
+        leaf.expand(7, &mut out);
+
+        let mut prefix = [0; 16];
+        leaf.expand(7, &mut prefix);
+        assert_eq!(prefix, out[..16]);
+
+        let key = leaf.sub_key::<SymmetricKey>(7);
+        assert_eq!(&key.key()[..], &out[..32]);
+        assert_eq!(&key.nonce_seed()[..], &out[32..64]);
+        assert_ne!(key.key(), leaf.sub_key::<SymmetricKey>(8).key());
+
+        let mut empty = [];
+        leaf.expand(7, &mut empty);
+    }
+}
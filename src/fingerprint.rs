@@ -76,6 +76,13 @@ impl SeedFingerprint {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for SeedFingerprint {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.arbitrary().map(Self)
+    }
+}
+
 impl FromStr for SeedFingerprint {
     type Err = ParseError;
 
@@ -163,3 +170,55 @@ fn test_seed_fingerprint_is_none() {
         "fingerprint from short seed should be `None`"
     );
 }
+
+/// [`proptest`] strategies for seeds and seed fingerprints.
+#[cfg(any(test, feature = "proptest"))]
+pub mod testing {
+    use alloc::vec::Vec;
+
+    use proptest::{collection::vec, prelude::*};
+
+    use super::SeedFingerprint;
+
+    /// Returns a strategy for generating seeds of every valid length (32 to 252 bytes,
+    /// inclusive).
+    pub fn arb_seed() -> impl Strategy<Value = Vec<u8>> {
+        vec(any::<u8>(), 32..=252)
+    }
+
+    /// Returns a strategy for generating arbitrary seed fingerprints.
+    pub fn arb_seed_fingerprint() -> impl Strategy<Value = SeedFingerprint> {
+        any::<[u8; 32]>().prop_map(SeedFingerprint::from_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use proptest::prelude::*;
+
+    use super::{
+        testing::{arb_seed, arb_seed_fingerprint},
+        SeedFingerprint,
+    };
+
+    proptest! {
+        #[test]
+        fn bech32m_round_trip(fp in arb_seed_fingerprint()) {
+            let encoded = fp.to_string();
+            assert!(encoded.starts_with("zip32seedfp1"));
+            assert_eq!(encoded.parse::<SeedFingerprint>().unwrap(), fp);
+        }
+
+        #[test]
+        fn distinct_seeds_have_distinct_fingerprints(a in arb_seed(), b in arb_seed()) {
+            if a != b {
+                assert_ne!(
+                    SeedFingerprint::from_seed(&a).unwrap(),
+                    SeedFingerprint::from_seed(&b).unwrap(),
+                );
+            }
+        }
+    }
+}
@@ -7,7 +7,7 @@
 #![deny(unsafe_code)]
 #![deny(rustdoc::broken_intra_doc_links)]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "alloc"))]
 extern crate alloc;
 
 #[cfg(feature = "std")]
@@ -19,10 +19,28 @@ use memuse::{self, DynamicUsage};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
 pub mod arbitrary;
+#[cfg(feature = "alloc")]
+pub mod cache;
+#[cfg(feature = "alloc")]
+pub mod discovery;
+pub mod expand;
 pub mod fingerprint;
 pub mod hardened_only;
+#[cfg(feature = "alloc")]
+pub mod path;
 pub mod registered;
 
+#[cfg(feature = "sapling")]
+pub mod sapling;
+
+#[cfg(feature = "alloc")]
+pub mod tracker;
+
+pub mod usage;
+
+#[cfg(any(test, feature = "test-vectors"))]
+pub mod test_vectors;
+
 /// A type-safe wrapper for account identifiers.
 ///
 /// Accounts are 31-bit unsigned integers, and are always treated as hardened in
@@ -65,15 +83,76 @@ impl ConditionallySelectable for AccountId {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for AccountId {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.int_in_range(0..=(1 << 31) - 1).map(AccountId)
+    }
+}
+
+impl core::fmt::Display for AccountId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::str::FromStr for AccountId {
+    type Err = ParseAccountIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // `u32::from_str` also accepts a leading `+`, which `Display` never produces.
+        Some(s)
+            .filter(|s| s.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|s| s.parse::<u32>().ok())
+            .and_then(|id| Self::try_from(id).ok())
+            .ok_or(ParseAccountIdError(()))
+    }
+}
+
 impl AccountId {
     /// The ID for account zero (the first account).
     pub const ZERO: Self = Self(0);
 
+    /// The largest valid account ID, `2^31 - 1`.
+    pub const MAX: Self = Self((1 << 31) - 1);
+
     /// Returns the next account ID in sequence, or `None` on overflow.
     pub fn next(&self) -> Option<Self> {
         Self::try_from(self.0 + 1).ok()
     }
 
+    /// Returns the previous account ID in sequence, or `None` if this is
+    /// [`AccountId::ZERO`].
+    pub fn prev(&self) -> Option<Self> {
+        self.0.checked_sub(1).map(Self)
+    }
+
+    /// Returns the account ID `n` steps after this one, or `None` on overflow.
+    pub fn checked_add(&self, n: u32) -> Option<Self> {
+        self.0.checked_add(n).and_then(|id| Self::try_from(id).ok())
+    }
+
+    /// Returns the account ID `n` steps before this one, or `None` on underflow.
+    pub fn checked_sub(&self, n: u32) -> Option<Self> {
+        self.0.checked_sub(n).map(Self)
+    }
+
+    /// Returns an iterator over the account IDs in the half-open range `start..end`.
+    pub fn range(start: AccountId, end: AccountId) -> AccountIdRange {
+        AccountIdRange {
+            start: start.0,
+            end: end.0.max(start.0),
+        }
+    }
+
+    /// Returns an iterator over the account IDs in the closed range `start..=end`.
+    pub fn range_inclusive(start: AccountId, end: AccountId) -> AccountIdRange {
+        AccountIdRange {
+            start: start.0,
+            end: (end.0 + 1).max(start.0),
+        }
+    }
+
     /// Constant function to construct an account ID from a u32.
     ///
     /// # Panics
@@ -87,6 +166,78 @@ impl AccountId {
     }
 }
 
+/// An iterator over a contiguous range of [`AccountId`]s.
+///
+/// Constructed by [`AccountId::range`] and [`AccountId::range_inclusive`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountIdRange {
+    // Invariant: start <= end <= 2^31.
+    start: u32,
+    end: u32,
+}
+
+impl From<core::ops::Range<AccountId>> for AccountIdRange {
+    fn from(range: core::ops::Range<AccountId>) -> Self {
+        AccountId::range(range.start, range.end)
+    }
+}
+
+impl From<core::ops::RangeInclusive<AccountId>> for AccountIdRange {
+    fn from(range: core::ops::RangeInclusive<AccountId>) -> Self {
+        AccountId::range_inclusive(*range.start(), *range.end())
+    }
+}
+
+impl Iterator for AccountIdRange {
+    type Item = AccountId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            let id = AccountId(self.start);
+            self.start += 1;
+            Some(id)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.start) as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for AccountIdRange {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            self.end -= 1;
+            Some(AccountId(self.end))
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for AccountIdRange {}
+
+impl core::iter::FusedIterator for AccountIdRange {}
+
+/// The error type returned when parsing an [`AccountId`] from a string fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseAccountIdError(());
+
+impl core::fmt::Display for ParseAccountIdError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "account ID must be a decimal integer in the range 0..2^31"
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseAccountIdError {}
+
 /// The error type returned when a checked integral type conversion fails.
 #[derive(Clone, Copy, Debug)]
 pub struct TryFromIntError(());
@@ -123,7 +274,7 @@ where
 /// A child index for a derived key.
 ///
 /// Only hardened derivation is supported.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ChildIndex(u32);
 
 impl ConstantTimeEq for ChildIndex {
@@ -132,6 +283,13 @@ impl ConstantTimeEq for ChildIndex {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for ChildIndex {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.int_in_range(0..=(1 << 31) - 1).map(ChildIndex::hardened)
+    }
+}
+
 impl ChildIndex {
     /// Parses the given ZIP 32 child index.
     ///
@@ -174,6 +332,13 @@ impl ConstantTimeEq for ChainCode {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for ChainCode {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.arbitrary().map(ChainCode)
+    }
+}
+
 impl ChainCode {
     /// Constructs a `ChainCode` from the given array.
     pub fn new(c: [u8; 32]) -> Self {
@@ -218,6 +383,13 @@ impl From<[u8; 11]> for DiversifierIndex {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for DiversifierIndex {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.arbitrary().map(DiversifierIndex)
+    }
+}
+
 impl TryFrom<u128> for DiversifierIndex {
     type Error = TryFromIntError;
 
@@ -253,6 +425,52 @@ impl From<DiversifierIndex> for u128 {
     }
 }
 
+/// Formats the diversifier index as a decimal integer.
+impl core::fmt::Display for DiversifierIndex {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&u128::from(*self), f)
+    }
+}
+
+/// Formats the diversifier index as a hexadecimal integer, as for [`u128`].
+///
+/// This is not the byte encoding used by ZIP 32, which is little-endian; use
+/// [`DiversifierIndex::to_le_hex`] for that.
+impl core::fmt::LowerHex for DiversifierIndex {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::LowerHex::fmt(&u128::from(*self), f)
+    }
+}
+
+/// Formats the diversifier index as a hexadecimal integer, as for [`u128`].
+///
+/// This is not the byte encoding used by ZIP 32, which is little-endian; use
+/// [`DiversifierIndex::to_le_hex`] for that.
+impl core::fmt::UpperHex for DiversifierIndex {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::UpperHex::fmt(&u128::from(*self), f)
+    }
+}
+
+/// Parses a diversifier index from a decimal integer less than 2^88, as produced by
+/// [`Display`](core::fmt::Display).
+///
+/// Use [`DiversifierIndex::from_le_hex`] to parse the little-endian hex encoding.
+impl core::str::FromStr for DiversifierIndex {
+    type Err = ParseDiversifierIndexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // `u128::from_str` also accepts a leading `+`, which `Display` never produces.
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseDiversifierIndexError(()));
+        }
+        s.parse::<u128>()
+            .ok()
+            .and_then(|j| Self::try_from(j).ok())
+            .ok_or(ParseDiversifierIndexError(()))
+    }
+}
+
 impl PartialOrd for DiversifierIndex {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
@@ -284,6 +502,51 @@ impl DiversifierIndex {
         &self.0
     }
 
+    /// Constructs a diversifier index from its little-endian byte encoding, which is the
+    /// encoding used by ZIP 32 and by [`DiversifierIndex::as_bytes`].
+    pub const fn from_le_bytes(bytes: [u8; 11]) -> Self {
+        DiversifierIndex(bytes)
+    }
+
+    /// Returns the little-endian byte encoding of the diversifier index.
+    pub const fn to_le_bytes(&self) -> [u8; 11] {
+        self.0
+    }
+
+    /// Constructs a diversifier index from its big-endian byte encoding.
+    pub fn from_be_bytes(mut bytes: [u8; 11]) -> Self {
+        bytes.reverse();
+        DiversifierIndex(bytes)
+    }
+
+    /// Returns the big-endian byte encoding of the diversifier index.
+    pub fn to_be_bytes(&self) -> [u8; 11] {
+        let mut bytes = self.0;
+        bytes.reverse();
+        bytes
+    }
+
+    /// Parses a diversifier index from the lowercase or uppercase hex encoding of its
+    /// little-endian byte encoding, which is exactly 22 hex digits with no prefix.
+    pub fn from_le_hex(s: &str) -> Result<Self, ParseDiversifierIndexError> {
+        if s.len() != 22 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseDiversifierIndexError(()));
+        }
+        let mut bytes = [0; 11];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)
+                .map_err(|_| ParseDiversifierIndexError(()))?;
+        }
+        Ok(DiversifierIndex(bytes))
+    }
+
+    /// Returns the lowercase hex encoding of the little-endian byte encoding of the
+    /// diversifier index, which is 22 hex digits with no prefix.
+    #[cfg(feature = "alloc")]
+    pub fn to_le_hex(&self) -> alloc::string::String {
+        hex::encode(self.0)
+    }
+
     /// Increments this index, failing on overflow.
     pub fn increment(&mut self) -> Result<(), DiversifierIndexOverflowError> {
         for k in 0..11 {
@@ -298,6 +561,19 @@ impl DiversifierIndex {
     }
 }
 
+/// The error type returned when parsing a [`DiversifierIndex`] from a string fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseDiversifierIndexError(());
+
+impl core::fmt::Display for ParseDiversifierIndexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid diversifier index encoding")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseDiversifierIndexError {}
+
 /// The error type returned when a [`DiversifierIndex`] increment fails.
 #[derive(Clone, Copy, Debug)]
 pub struct DiversifierIndexOverflowError;
@@ -319,7 +595,10 @@ impl std::error::Error for DiversifierIndexOverflowError {}
 /// to other people. For example, a user can give an external incoming viewing key to a
 /// merchant terminal, enabling it to only detect "real" transactions from customers and
 /// not internal transactions from the wallet.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+///
+/// These scopes apply to every pool. The transparent pool has further scopes, which are
+/// modelled by [`TransparentScope`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Scope {
     /// A scope used for wallet-external operations, namely deriving addresses to give to
     /// other users in order to receive funds.
@@ -331,11 +610,280 @@ pub enum Scope {
 
 memuse::impl_no_dynamic_usage!(Scope);
 
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for Scope {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.choose(&Scope::ALL).copied()
+    }
+}
+
+impl Scope {
+    /// All scopes, in order of their child indices.
+    pub const ALL: [Scope; 2] = [Scope::External, Scope::Internal];
+
+    /// Returns the child index used for this scope in ZIP 32 derivation paths.
+    ///
+    /// This is the (non-hardened) index of the "change" level in transparent ([BIP 44])
+    /// derivation paths: 0 for [`Scope::External`] and 1 for [`Scope::Internal`].
+    /// Sapling and Orchard derivation paths have no such level; instead, internal keys are
+    /// obtained from the full viewing key via the [Sapling] and [Orchard] internal key
+    /// derivations defined in ZIP 32, but the same values are used to identify the scope.
+    ///
+    /// [BIP 44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki#change
+    /// [Sapling]: https://zips.z.cash/zip-0032#sapling-internal-key-derivation
+    /// [Orchard]: https://zips.z.cash/zip-0032#orchard-internal-key-derivation
+    pub const fn child_index(self) -> u32 {
+        match self {
+            Scope::External => 0,
+            Scope::Internal => 1,
+        }
+    }
+}
+
+impl TryFrom<u32> for Scope {
+    type Error = TryFromIntError;
+
+    fn try_from(child_index: u32) -> Result<Self, Self::Error> {
+        match child_index {
+            0 => Ok(Scope::External),
+            1 => Ok(Scope::Internal),
+            _ => Err(TryFromIntError(())),
+        }
+    }
+}
+
+impl From<Scope> for u32 {
+    fn from(scope: Scope) -> Self {
+        scope.child_index()
+    }
+}
+
+impl core::fmt::Display for Scope {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&TransparentScope::from(*self), f)
+    }
+}
+
+impl core::str::FromStr for Scope {
+    type Err = ParseScopeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<TransparentScope>()?
+            .scope()
+            .ok_or(ParseScopeError(()))
+    }
+}
+
+/// The scope of a transparent key or address, which is the "change" level of a
+/// transparent ([BIP 44]) derivation path.
+///
+/// Besides the [`Scope`]s that apply to every pool, the transparent pool has an
+/// [ephemeral](TransparentScope::Ephemeral) scope. Further scopes may be added as new
+/// transparent key usages are specified, so matches on this enum must include a wildcard
+/// arm.
+///
+/// [BIP 44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki#change
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum TransparentScope {
+    /// The transparent equivalent of [`Scope::External`].
+    External,
+    /// The transparent equivalent of [`Scope::Internal`].
+    Internal,
+    /// A scope used for single-use transparent addresses, such as those that receive
+    /// funds from a shielded pool before sending them on to a TEX address.
+    ///
+    /// Defined in [ZIP 320: Defining an Address Type to which funds can only be sent from
+    /// Transparent Addresses][zip320].
+    ///
+    /// [zip320]: https://zips.z.cash/zip-0320
+    Ephemeral,
+}
+
+memuse::impl_no_dynamic_usage!(TransparentScope);
+
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for TransparentScope {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.choose(&TransparentScope::ALL).copied()
+    }
+}
+
+impl TransparentScope {
+    /// All transparent scopes, in order of their child indices.
+    pub const ALL: [TransparentScope; 3] = [
+        TransparentScope::External,
+        TransparentScope::Internal,
+        TransparentScope::Ephemeral,
+    ];
+
+    /// Returns the child index used for this scope at the "change" level of transparent
+    /// derivation paths: 0 for [`TransparentScope::External`], 1 for
+    /// [`TransparentScope::Internal`], and 2 for [`TransparentScope::Ephemeral`] (as
+    /// specified in [ZIP 320]).
+    ///
+    /// [ZIP 320]: https://zips.z.cash/zip-0320
+    pub const fn child_index(self) -> u32 {
+        match self {
+            TransparentScope::External => 0,
+            TransparentScope::Internal => 1,
+            TransparentScope::Ephemeral => 2,
+        }
+    }
+
+    /// Returns the [`Scope`] corresponding to this transparent scope, or `None` if it is
+    /// specific to the transparent pool.
+    pub const fn scope(self) -> Option<Scope> {
+        match self {
+            TransparentScope::External => Some(Scope::External),
+            TransparentScope::Internal => Some(Scope::Internal),
+            TransparentScope::Ephemeral => None,
+        }
+    }
+
+    /// Returns whether this scope applies to keys and addresses in the given pool.
+    ///
+    /// Scopes that correspond to a [`Scope`] apply to every pool; the others only apply
+    /// to [`Pool::Transparent`].
+    pub fn applies_to(self, pool: Pool) -> bool {
+        pool == Pool::Transparent || self.scope().is_some()
+    }
+}
+
+impl From<Scope> for TransparentScope {
+    fn from(scope: Scope) -> Self {
+        match scope {
+            Scope::External => TransparentScope::External,
+            Scope::Internal => TransparentScope::Internal,
+        }
+    }
+}
+
+impl TryFrom<u32> for TransparentScope {
+    type Error = TryFromIntError;
+
+    fn try_from(child_index: u32) -> Result<Self, Self::Error> {
+        match child_index {
+            0 => Ok(TransparentScope::External),
+            1 => Ok(TransparentScope::Internal),
+            2 => Ok(TransparentScope::Ephemeral),
+            _ => Err(TryFromIntError(())),
+        }
+    }
+}
+
+impl From<TransparentScope> for u32 {
+    fn from(scope: TransparentScope) -> Self {
+        scope.child_index()
+    }
+}
+
+impl core::fmt::Display for TransparentScope {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TransparentScope::External => write!(f, "external"),
+            TransparentScope::Internal => write!(f, "internal"),
+            TransparentScope::Ephemeral => write!(f, "ephemeral"),
+        }
+    }
+}
+
+impl core::str::FromStr for TransparentScope {
+    type Err = ParseScopeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "external" => Ok(TransparentScope::External),
+            "internal" => Ok(TransparentScope::Internal),
+            "ephemeral" => Ok(TransparentScope::Ephemeral),
+            _ => Err(ParseScopeError(())),
+        }
+    }
+}
+
+/// A value pool whose keys are derived according to [ZIP 32] or [BIP 44].
+///
+/// [ZIP 32]: https://zips.z.cash/zip-0032
+/// [BIP 44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Pool {
+    /// The transparent pool.
+    Transparent,
+    /// The Sapling shielded pool.
+    Sapling,
+    /// The Orchard shielded pool.
+    Orchard,
+}
+
+memuse::impl_no_dynamic_usage!(Pool);
+
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for Pool {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.choose(&[Pool::Transparent, Pool::Sapling, Pool::Orchard])
+            .copied()
+    }
+}
+
+/// The error type returned when parsing a [`Scope`] or [`TransparentScope`] from a
+/// string fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseScopeError(());
+
+impl core::fmt::Display for ParseScopeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "unrecognized scope")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseScopeError {}
+
+/// [`proptest`] strategies for the types in this crate.
+#[cfg(any(test, feature = "proptest"))]
+pub mod testing {
+    use proptest::prelude::*;
+
+    use super::{AccountId, ChildIndex, DiversifierIndex, Scope, TransparentScope};
+
+    /// Returns a strategy for generating arbitrary account identifiers.
+    pub fn arb_account_id() -> impl Strategy<Value = AccountId> {
+        (0u32..(1 << 31)).prop_map(AccountId)
+    }
+
+    /// Returns a strategy for generating arbitrary (hardened) child indices.
+    pub fn arb_child_index() -> impl Strategy<Value = ChildIndex> {
+        (0u32..(1 << 31)).prop_map(ChildIndex::hardened)
+    }
+
+    /// Returns a strategy for generating arbitrary diversifier indices.
+    pub fn arb_diversifier_index() -> impl Strategy<Value = DiversifierIndex> {
+        any::<[u8; 11]>().prop_map(DiversifierIndex::from)
+    }
+
+    /// Returns a strategy for generating arbitrary scopes.
+    pub fn arb_scope() -> impl Strategy<Value = Scope> {
+        prop::sample::select(&Scope::ALL[..])
+    }
+
+    /// Returns a strategy for generating arbitrary transparent scopes.
+    pub fn arb_transparent_scope() -> impl Strategy<Value = TransparentScope> {
+        prop::sample::select(&TransparentScope::ALL[..])
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{AccountId, DiversifierIndex};
+    use alloc::string::ToString;
+
+    use super::{
+        testing::{arb_account_id, arb_diversifier_index, arb_scope, arb_transparent_scope},
+        AccountId, AccountIdRange, ChildIndex, DiversifierIndex, Pool, Scope, TransparentScope,
+    };
 
     use assert_matches::assert_matches;
+    use proptest::prelude::*;
 
     #[test]
     fn account_id_next() {
@@ -343,7 +891,89 @@ mod tests {
         assert_eq!(zero.next(), AccountId::try_from(1).ok());
 
         let max_id = AccountId::try_from((1 << 31) - 1).unwrap();
+        assert_eq!(max_id, AccountId::MAX);
         assert_eq!(max_id.next(), None);
+        assert_matches!(AccountId::try_from(1 << 31), Err(_));
+    }
+
+    #[test]
+    fn account_id_prev() {
+        assert_eq!(AccountId::ZERO.prev(), None);
+        assert_eq!(AccountId::const_from_u32(1).prev(), Some(AccountId::ZERO));
+        assert_eq!(
+            AccountId::MAX.prev(),
+            AccountId::try_from((1 << 31) - 2).ok()
+        );
+    }
+
+    #[test]
+    fn account_id_checked_offsets() {
+        assert_eq!(AccountId::ZERO.checked_add(0), Some(AccountId::ZERO));
+        assert_eq!(
+            AccountId::ZERO.checked_add((1 << 31) - 1),
+            Some(AccountId::MAX)
+        );
+        assert_eq!(AccountId::ZERO.checked_add(1 << 31), None);
+        assert_eq!(AccountId::MAX.checked_add(1), None);
+        assert_eq!(AccountId::MAX.checked_add(u32::MAX), None);
+
+        assert_eq!(
+            AccountId::MAX.checked_sub((1 << 31) - 1),
+            Some(AccountId::ZERO)
+        );
+        assert_eq!(AccountId::MAX.checked_sub(1 << 31), None);
+        assert_eq!(AccountId::ZERO.checked_sub(1), None);
+    }
+
+    #[test]
+    fn account_id_range() {
+        let ids = AccountId::range(AccountId::ZERO, AccountId::const_from_u32(3));
+        assert_eq!(ids.len(), 3);
+        assert_eq!(
+            ids.map(u32::from).collect::<alloc::vec::Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        let mut ids = AccountIdRange::from(AccountId::ZERO..=AccountId::const_from_u32(2));
+        assert_eq!(ids.next_back(), Some(AccountId::const_from_u32(2)));
+        assert_eq!(ids.next(), Some(AccountId::ZERO));
+        assert_eq!(ids.next_back(), Some(AccountId::const_from_u32(1)));
+        assert_eq!(ids.next(), None);
+        assert_eq!(ids.next_back(), None);
+
+        // Ranges at the top of the account ID space.
+        let top = AccountId::MAX.prev().unwrap();
+        let ids = AccountId::range_inclusive(top, AccountId::MAX);
+        assert_eq!(
+            ids.rev().collect::<alloc::vec::Vec<_>>(),
+            [AccountId::MAX, top]
+        );
+        assert_eq!(AccountId::range(top, AccountId::MAX).count(), 1);
+        assert_eq!(
+            AccountId::range_inclusive(AccountId::MAX, AccountId::MAX).count(),
+            1
+        );
+
+        // Empty and inverted ranges.
+        assert_eq!(AccountId::range(AccountId::MAX, AccountId::MAX).count(), 0);
+        assert_eq!(AccountId::range(AccountId::MAX, AccountId::ZERO).count(), 0);
+        assert_eq!(AccountId::range_inclusive(AccountId::MAX, top).count(), 0);
+    }
+
+    #[test]
+    fn account_id_string_round_trip() {
+        for id in [
+            AccountId::ZERO,
+            AccountId::const_from_u32(42),
+            AccountId::MAX,
+        ] {
+            assert_eq!(id.to_string().parse::<AccountId>(), Ok(id));
+        }
+        assert_eq!(AccountId::MAX.to_string(), "2147483647");
+        assert_matches!("2147483648".parse::<AccountId>(), Err(_));
+        assert_matches!("-1".parse::<AccountId>(), Err(_));
+        assert_matches!("+5".parse::<AccountId>(), Err(_));
+        assert_matches!("".parse::<AccountId>(), Err(_));
     }
 
     #[test]
@@ -442,4 +1072,240 @@ mod tests {
         assert!(DiversifierIndex::from(3u64) == DiversifierIndex::from(3u64));
         assert!(DiversifierIndex::from(u64::MAX) == DiversifierIndex::from(u64::MAX));
     }
+
+    #[test]
+    fn diversifier_index_strings() {
+        use alloc::format;
+
+        let di = DiversifierIndex::from(258u32);
+        assert_eq!(di.to_string(), "258");
+        assert_eq!("258".parse(), Ok(di));
+        assert_eq!("0258".parse(), Ok(di));
+
+        // The hex formats are those of the integer, with all flags honoured.
+        assert_eq!(format!("{:x}", di), "102");
+        assert_eq!(format!("{:#X}", di), "0x102");
+        assert_eq!(format!("{:>6}", di), "   258");
+        assert_eq!(format!("{:*<6x}", di), "102***");
+        assert_eq!(format!("{:#08x}", di), "0x000102");
+
+        // The little-endian hex encoding is separate from the integer formats.
+        #[cfg(feature = "alloc")]
+        assert_eq!(di.to_le_hex(), "0201000000000000000000");
+        assert_eq!(
+            DiversifierIndex::from_le_hex("0201000000000000000000"),
+            Ok(di)
+        );
+        assert_eq!(
+            DiversifierIndex::from_le_hex("0201000000000000000000".to_uppercase().as_str()),
+            Ok(di)
+        );
+
+        let max = DiversifierIndex::from([0xff; 11]);
+        assert_eq!(max.to_string(), "309485009821345068724781055");
+        assert_eq!("309485009821345068724781055".parse(), Ok(max));
+        assert_eq!(format!("{:X}", max), "FFFFFFFFFFFFFFFFFFFFFF");
+        #[cfg(feature = "alloc")]
+        assert_eq!(max.to_le_hex(), "ffffffffffffffffffffff");
+
+        // Strings of hex digits are never parsed as decimal, or vice versa.
+        assert_eq!(
+            "0201000000000000000000"
+                .parse::<DiversifierIndex>()
+                .map(u128::from),
+            Ok(201_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            DiversifierIndex::from_le_hex("0201000000000000000000").map(u128::from),
+            Ok(258)
+        );
+
+        for s in [
+            "",
+            "+1",
+            "-1",
+            " 1",
+            "309485009821345068724781056",
+            "0x102",
+            "0x0201000000000000000000",
+            "102a",
+        ] {
+            assert!(s.parse::<DiversifierIndex>().is_err(), "{:?}", s);
+        }
+        for s in [
+            "",
+            "02010000000000000000",
+            "020100000000000000000000",
+            "0x0201000000000000000000",
+            "+201000000000000000000",
+            "g201000000000000000000",
+            "é01000000000000000000",
+        ] {
+            assert!(DiversifierIndex::from_le_hex(s).is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn diversifier_index_byte_orders() {
+        let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        let di = DiversifierIndex::from_le_bytes(bytes);
+        assert_eq!(di, DiversifierIndex::from(bytes));
+        assert_eq!(di.to_le_bytes(), bytes);
+        assert_eq!(di.to_be_bytes(), [11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(DiversifierIndex::from_be_bytes(di.to_be_bytes()), di);
+        assert_eq!(
+            u128::from(DiversifierIndex::from_be_bytes([
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2
+            ])),
+            0x0102
+        );
+    }
+
+    #[test]
+    fn scope_child_index() {
+        assert_eq!(Scope::External.child_index(), 0);
+        assert_eq!(Scope::Internal.child_index(), 1);
+        for scope in Scope::ALL {
+            assert_matches!(Scope::try_from(scope.child_index()), Ok(s) if s == scope);
+            assert_eq!(u32::from(scope), scope.child_index());
+            assert_eq!(
+                TransparentScope::from(scope).child_index(),
+                scope.child_index()
+            );
+        }
+        assert_matches!(Scope::try_from(2u32), Err(_));
+        assert_matches!(Scope::try_from(u32::MAX), Err(_));
+
+        assert_eq!(TransparentScope::Ephemeral.child_index(), 2);
+        for scope in TransparentScope::ALL {
+            assert_matches!(
+                TransparentScope::try_from(scope.child_index()),
+                Ok(s) if s == scope
+            );
+            assert_eq!(u32::from(scope), scope.child_index());
+        }
+        assert_matches!(TransparentScope::try_from(3u32), Err(_));
+    }
+
+    #[test]
+    fn scope_string_round_trip() {
+        for scope in Scope::ALL {
+            assert_eq!(scope.to_string().parse::<Scope>(), Ok(scope));
+        }
+        for scope in TransparentScope::ALL {
+            assert_eq!(scope.to_string().parse::<TransparentScope>(), Ok(scope));
+        }
+        assert_eq!(Scope::External.to_string(), "external");
+        assert_eq!(Scope::Internal.to_string(), "internal");
+        assert_eq!(TransparentScope::Ephemeral.to_string(), "ephemeral");
+        assert_matches!("ephemeral".parse::<Scope>(), Err(_));
+        assert_matches!("External".parse::<Scope>(), Err(_));
+        assert_matches!("".parse::<TransparentScope>(), Err(_));
+    }
+
+    #[test]
+    fn scope_ord() {
+        assert!(Scope::External < Scope::Internal);
+        assert!(TransparentScope::Internal < TransparentScope::Ephemeral);
+        let mut scopes = TransparentScope::ALL;
+        scopes.sort_unstable();
+        assert_eq!(scopes, TransparentScope::ALL);
+    }
+
+    #[test]
+    fn transparent_scopes() {
+        for scope in Scope::ALL {
+            assert_eq!(TransparentScope::from(scope).scope(), Some(scope));
+        }
+        assert_eq!(TransparentScope::Ephemeral.scope(), None);
+        for pool in [Pool::Sapling, Pool::Orchard] {
+            assert!(TransparentScope::External.applies_to(pool));
+            assert!(TransparentScope::Internal.applies_to(pool));
+            assert!(!TransparentScope::Ephemeral.applies_to(pool));
+        }
+        for scope in TransparentScope::ALL {
+            assert!(scope.applies_to(Pool::Transparent));
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_values_are_valid() {
+        use ::arbitrary::Unstructured;
+
+        for byte in [0x00, 0x7f, 0x80, 0xff] {
+            let data = [byte; 64];
+            let mut u = Unstructured::new(&data);
+            let id: AccountId = u.arbitrary().unwrap();
+            assert!(id <= AccountId::MAX);
+            let index: ChildIndex = u.arbitrary().unwrap();
+            assert!(index.index() >= (1 << 31));
+            let di: DiversifierIndex = u.arbitrary().unwrap();
+            assert_eq!(di.as_bytes(), &[byte; 11]);
+            let scope: Scope = u.arbitrary().unwrap();
+            assert!(Scope::ALL.contains(&scope));
+            let scope: TransparentScope = u.arbitrary().unwrap();
+            assert!(TransparentScope::ALL.contains(&scope));
+            let _: Pool = u.arbitrary().unwrap();
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn account_id_round_trip(id in arb_account_id()) {
+            assert_eq!(AccountId::try_from(u32::from(id)).ok(), Some(id));
+            assert_eq!(id.to_string().parse::<AccountId>().ok(), Some(id));
+            assert_eq!(
+                ChildIndex::from(id),
+                ChildIndex::hardened(u32::from(id))
+            );
+        }
+
+        #[test]
+        fn diversifier_index_u128_round_trip(di in arb_diversifier_index()) {
+            let j = u128::from(di);
+            assert!(j < (1 << 88));
+            assert_eq!(DiversifierIndex::try_from(j).ok(), Some(di));
+        }
+
+        #[test]
+        fn diversifier_index_ord_matches_u128(
+            a in arb_diversifier_index(),
+            b in arb_diversifier_index(),
+        ) {
+            assert_eq!(a.cmp(&b), u128::from(a).cmp(&u128::from(b)));
+            assert_eq!(a == b, u128::from(a) == u128::from(b));
+        }
+
+        #[test]
+        fn diversifier_index_string_round_trip(di in arb_diversifier_index()) {
+            assert_eq!(di.to_string(), u128::from(di).to_string());
+            assert_eq!(di.to_string().parse::<DiversifierIndex>(), Ok(di));
+            #[cfg(feature = "alloc")]
+            {
+                assert_eq!(DiversifierIndex::from_le_hex(&di.to_le_hex()), Ok(di));
+                assert_eq!(
+                    DiversifierIndex::from_le_hex(&di.to_le_hex().to_uppercase()),
+                    Ok(di)
+                );
+            }
+            assert_eq!(alloc::format!("{:x}", di), alloc::format!("{:x}", u128::from(di)));
+            assert_eq!(
+                u128::from_str_radix(&alloc::format!("{:X}", di), 16).ok(),
+                Some(u128::from(di))
+            );
+        }
+
+        #[test]
+        fn scope_round_trip(scope in arb_scope()) {
+            assert_eq!(Scope::try_from(scope.child_index()).ok(), Some(scope));
+            assert_eq!(scope.to_string().parse::<Scope>().ok(), Some(scope));
+        }
+
+        #[test]
+        fn transparent_scope_round_trip(scope in arb_transparent_scope()) {
+            assert_eq!(TransparentScope::try_from(scope.child_index()).ok(), Some(scope));
+            assert_eq!(scope.to_string().parse::<TransparentScope>().ok(), Some(scope));
+        }
+    }
 }
@@ -0,0 +1,238 @@
+//! Text encoding of hardened-only derivation paths.
+//!
+//! A path is a sequence of elements separated by `/`, optionally preceded by `m`. Each
+//! element is a hardened index written as a decimal number less than 2^31 followed by
+//! `'` or `h`, and may be followed by `:` and a hex-encoded tag. Tags are only meaningful
+//! for [registered key derivation]. For example:
+//!
+//! - `m/32'/133'/0'`
+//! - `2':7472616e73/3h`
+//!
+//! A [`Path`] is displayed in canonical form, which starts with `m`, marks every element
+//! with `'`, and writes tags in lowercase hex.
+//!
+//! [registered key derivation]: crate::registered
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{
+    registered::{OwnedPathElement, PathElement},
+    ChildIndex,
+};
+
+/// A parsed hardened-only derivation path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path(Vec<OwnedPathElement>);
+
+impl Path {
+    /// Returns the elements of this path.
+    pub fn elements(&self) -> &[OwnedPathElement] {
+        &self.0
+    }
+
+    /// Returns `true` if any element of this path has a non-empty tag.
+    pub fn has_tags(&self) -> bool {
+        self.0.iter().any(|elem| !elem.tag().is_empty())
+    }
+
+    /// Returns the child indices of the elements of this path, ignoring their tags.
+    pub fn child_indices(&self) -> Vec<ChildIndex> {
+        self.0.iter().map(|elem| elem.child_index()).collect()
+    }
+
+    /// Returns this path as a registered key derivation subpath.
+    pub fn subpath(&self) -> Vec<PathElement<'_>> {
+        self.0.iter().map(OwnedPathElement::as_borrowed).collect()
+    }
+}
+
+impl FromStr for Path {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = if s == "m" {
+            ""
+        } else {
+            s.strip_prefix("m/").unwrap_or(s)
+        };
+        if s.is_empty() {
+            return Ok(Path(Vec::new()));
+        }
+
+        s.split('/')
+            .enumerate()
+            .map(|(i, elem)| {
+                if elem.is_empty() {
+                    return Err(ParseError::EmptyElement(i));
+                }
+                let (index, tag) = match elem.split_once(':') {
+                    Some((index, tag)) => (
+                        index,
+                        hex::decode(tag).map_err(|_| ParseError::InvalidTag(i))?,
+                    ),
+                    None => (elem, Vec::new()),
+                };
+                let index = index
+                    .strip_suffix('\'')
+                    .or_else(|| index.strip_suffix('h'))
+                    .ok_or(ParseError::NotHardened(i))?;
+                if !index.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ParseError::InvalidIndex(i));
+                }
+                let index = index
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|index| *index < (1 << 31))
+                    .ok_or(ParseError::InvalidIndex(i))?;
+                Ok(OwnedPathElement::from(PathElement::new(
+                    ChildIndex::hardened(index),
+                    &tag,
+                )))
+            })
+            .collect::<Result<_, _>>()
+            .map(Path)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for elem in &self.0 {
+            write!(f, "/{}'", elem.child_index().index() & !(1 << 31))?;
+            if !elem.tag().is_empty() {
+                write!(f, ":")?;
+                for b in elem.tag() {
+                    write!(f, "{:02x}", b)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors that can occur when parsing a derivation path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The element at the given position was empty.
+    EmptyElement(usize),
+    /// The element at the given position was not marked as hardened; only hardened
+    /// derivation is supported.
+    NotHardened(usize),
+    /// The index of the element at the given position was not a decimal number less than
+    /// 2^31.
+    InvalidIndex(usize),
+    /// The tag of the element at the given position was not a valid hex string.
+    InvalidTag(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::EmptyElement(i) => write!(f, "path element {} is empty", i),
+            ParseError::NotHardened(i) => write!(
+                f,
+                "path element {} is not hardened; only hardened derivation is supported",
+                i
+            ),
+            ParseError::InvalidIndex(i) => write!(
+                f,
+                "path element {} must be a decimal index less than 2^31",
+                i
+            ),
+            ParseError::InvalidTag(i) => {
+                write!(f, "path element {} has a tag that is not valid hex", i)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::{ParseError, Path};
+    use crate::{
+        registered::{OwnedPathElement, PathElement},
+        ChildIndex,
+    };
+
+    fn element(index: u32, tag: &[u8]) -> OwnedPathElement {
+        PathElement::new(ChildIndex::hardened(index), tag).into()
+    }
+
+    #[test]
+    fn parse_valid_paths() {
+        for s in ["", "m", "m/"] {
+            assert_eq!(s.parse::<Path>(), Ok(Path(vec![])));
+        }
+        let expected = Path(vec![element(32, &[]), element(133, &[]), element(0, &[])]);
+        for s in [
+            "m/32'/133'/0'",
+            "32'/133'/0'",
+            "m/32h/133h/0h",
+            "32'/133h/0'",
+        ] {
+            assert_eq!(s.parse::<Path>().as_ref(), Ok(&expected));
+        }
+        assert_eq!(
+            "2':7472616e73/3h/2147483647'".parse::<Path>(),
+            Ok(Path(vec![
+                element(2, b"trans"),
+                element(3, &[]),
+                element(0x7fff_ffff, &[]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_invalid_paths() {
+        for (s, err) in [
+            ("m//1'", ParseError::EmptyElement(0)),
+            ("1'/", ParseError::EmptyElement(1)),
+            ("/1'", ParseError::EmptyElement(0)),
+            ("m1'", ParseError::InvalidIndex(0)),
+            ("1'/2", ParseError::NotHardened(1)),
+            ("2147483648'", ParseError::InvalidIndex(0)),
+            ("+1'", ParseError::InvalidIndex(0)),
+            ("x'", ParseError::InvalidIndex(0)),
+            ("'", ParseError::InvalidIndex(0)),
+            ("1':abc", ParseError::InvalidTag(0)),
+            ("1':zz", ParseError::InvalidTag(0)),
+            ("1':+1", ParseError::InvalidTag(0)),
+            ("1':é0", ParseError::InvalidTag(0)),
+        ] {
+            assert_eq!(s.parse::<Path>(), Err(err));
+        }
+    }
+
+    #[test]
+    fn display_is_canonical() {
+        let path = "32h/133':00FF/0'".parse::<Path>().unwrap();
+        assert_eq!(path.to_string(), "m/32'/133':00ff/0'");
+        assert_eq!(path.to_string().parse(), Ok(path));
+        assert_eq!(Path(vec![]).to_string(), "m");
+    }
+
+    #[test]
+    fn accessors() {
+        let path = "1'/2':00ff".parse::<Path>().unwrap();
+        assert!(path.has_tags());
+        assert_eq!(
+            path.child_indices(),
+            [ChildIndex::hardened(1), ChildIndex::hardened(2)]
+        );
+        assert_eq!(
+            path.subpath(),
+            [
+                PathElement::new(ChildIndex::hardened(1), &[]),
+                PathElement::new(ChildIndex::hardened(2), &[0x00, 0xff]),
+            ]
+        );
+        assert!(!"1'/2'".parse::<Path>().unwrap().has_tags());
+    }
+}
@@ -36,17 +36,30 @@
 //! [ZIP process]: https://zips.z.cash/zip-0000
 //! [`zip32::arbitrary`]: `crate::arbitrary`
 
-use core::fmt::Display;
+use core::fmt::{self, Display};
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use subtle::{Choice, ConstantTimeEq};
 use zcash_spec::PrfExpand;
+use zeroize::Zeroize;
 
 use crate::{
+    expand::LeafKey,
     hardened_only::{Context, HardenedOnlyCkdDomain, HardenedOnlyKey},
     ChainCode, ChildIndex,
 };
 
+#[cfg(feature = "alloc")]
+use crate::hardened_only::BatchContext;
+
 use super::with_ikm;
 
+#[derive(Clone)]
 struct Registered;
 
 impl Context for Registered {
@@ -54,6 +67,11 @@ impl Context for Registered {
     const CKD_DOMAIN: HardenedOnlyCkdDomain = PrfExpand::REGISTERED_ZIP32_CHILD;
 }
 
+#[cfg(feature = "alloc")]
+impl BatchContext for Registered {
+    const CKD_DOMAIN_SEPARATOR: u8 = 0xac;
+}
+
 /// An error that occurred in cryptovalue derivation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DerivationError {
@@ -90,8 +108,63 @@ impl Display for DerivationError {
 #[cfg(feature = "std")]
 impl std::error::Error for DerivationError {}
 
+/// An error that occurred while deriving a [`SubtreeRoot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtreeRootError {
+    /// The context string or seed was invalid.
+    Derivation(DerivationError),
+    /// The root index was a hardened ZIP number. Subtrees rooted at ZIP numbers must be
+    /// derived with [`ZipRoot`].
+    RootIndexIsZipNumber,
+}
+
+impl From<DerivationError> for SubtreeRootError {
+    fn from(e: DerivationError) -> Self {
+        SubtreeRootError::Derivation(e)
+    }
+}
+
+impl Display for SubtreeRootError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SubtreeRootError::Derivation(e) => e.fmt(f),
+            SubtreeRootError::RootIndexIsZipNumber => write!(
+                f,
+                "Subtree root indices must not be hardened ZIP numbers; use ZipRoot instead."
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SubtreeRootError {}
+
+/// Checks that the given context string and seed are valid inputs to master key
+/// generation.
+///
+/// The same checks apply to registered and [ad-hoc] master key generation. The functions
+/// in this module perform them, returning the error; [`arbitrary::SecretKey::from_path`]
+/// instead panics on invalid inputs, so callers taking inputs from outside the program
+/// should check them with this function first.
+///
+/// [ad-hoc]: crate::arbitrary
+/// [`arbitrary::SecretKey::from_path`]: crate::arbitrary::SecretKey::from_path
+pub fn check_master_inputs(context_string: &[u8], seed: &[u8]) -> Result<(), DerivationError> {
+    if context_string.is_empty() || context_string.len() > 252 {
+        return Err(DerivationError::ContextStringInvalid);
+    }
+    if seed.len() < 32 || seed.len() > 252 {
+        return Err(DerivationError::SeedInvalid);
+    }
+    Ok(())
+}
+
 /// A ZIP 32 registered key derivation path element, consisting of a child index and an
 /// optionally-empty tag value.
+///
+/// ZIP 32 does not limit the length of a tag, so no maximum is enforced: restricting it
+/// here would make some registered keys underivable with this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct PathElement<'a> {
     child_index: ChildIndex,
     tag: &'a [u8],
@@ -114,15 +187,144 @@ impl<'a> PathElement<'a> {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> ::arbitrary::Arbitrary<'a> for PathElement<'a> {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        Ok(Self::new(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+/// An owned ZIP 32 registered key derivation path element, consisting of a child index and
+/// an optionally-empty tag value.
+///
+/// This is useful when path elements are constructed at runtime, and so cannot borrow
+/// their tags. Use [`OwnedPathElement::as_borrowed`] to obtain a [`PathElement`] for
+/// derivation.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedPathElement {
+    child_index: ChildIndex,
+    tag: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedPathElement {
+    /// Constructs a new [`OwnedPathElement`] from its constituent parts.
+    pub fn new(child_index: ChildIndex, tag: impl Into<Vec<u8>>) -> Self {
+        Self {
+            child_index,
+            tag: tag.into(),
+        }
+    }
+
+    /// Returns the index at which the child key will be derived.
+    pub fn child_index(&self) -> ChildIndex {
+        self.child_index
+    }
+
+    /// Returns the tag that will be used in derivation of the child key.
+    pub fn tag(&self) -> &[u8] {
+        &self.tag
+    }
+
+    /// Returns a [`PathElement`] borrowing this element's tag.
+    pub fn as_borrowed(&self) -> PathElement<'_> {
+        PathElement::new(self.child_index, &self.tag)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<PathElement<'_>> for OwnedPathElement {
+    fn from(elem: PathElement<'_>) -> Self {
+        Self {
+            child_index: elem.child_index,
+            tag: elem.tag.to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a OwnedPathElement> for PathElement<'a> {
+    fn from(elem: &'a OwnedPathElement) -> Self {
+        elem.as_borrowed()
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "arbitrary"))]
+impl<'a> ::arbitrary::Arbitrary<'a> for OwnedPathElement {
+    fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        u.arbitrary::<PathElement<'a>>().map(Self::from)
+    }
+}
+
+/// A builder for registered key derivation subpaths.
+///
+/// ```
+/// use zip32::{registered::{SecretKey, SubpathBuilder}, ChildIndex};
+///
+/// let subpath = SubpathBuilder::new()
+///     .child(ChildIndex::hardened(7))
+///     .child_with_tag(ChildIndex::hardened(0), b"payment".to_vec())
+///     .build();
+/// let borrowed: Vec<_> = subpath.iter().map(|elem| elem.as_borrowed()).collect();
+/// let key = SecretKey::from_subpath(b"Zcash example", &[0; 32], 1, &borrowed).unwrap();
+/// ```
+///
+/// The builder does not validate tags, because every tag is valid in ZIP 32 (see
+/// [`PathElement`]).
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct SubpathBuilder {
+    elements: Vec<OwnedPathElement>,
+}
+
+#[cfg(feature = "alloc")]
+impl SubpathBuilder {
+    /// Constructs a builder for an empty subpath.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a path element with the given child index and an empty tag.
+    pub fn child(self, child_index: ChildIndex) -> Self {
+        self.child_with_tag(child_index, Vec::new())
+    }
+
+    /// Appends a path element with the given child index and tag.
+    pub fn child_with_tag(mut self, child_index: ChildIndex, tag: impl Into<Vec<u8>>) -> Self {
+        self.elements.push(OwnedPathElement::new(child_index, tag));
+        self
+    }
+
+    /// Returns the subpath.
+    pub fn build(self) -> Vec<OwnedPathElement> {
+        self.elements
+    }
+}
+
 /// A registered extended secret key.
 ///
 /// Defined in [ZIP 32: Registered key derivation][regkd].
 ///
 /// [regkd]: https://zips.z.cash/zip-0032#specification-registered-key-derivation
+#[derive(Clone)]
 pub struct SecretKey {
     inner: HardenedOnlyKey<Registered>,
 }
 
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Neither the key material nor the chain code is rendered.
+        f.debug_struct("SecretKey").finish_non_exhaustive()
+    }
+}
+
+impl ConstantTimeEq for SecretKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.inner.ct_eq(&other.inner)
+    }
+}
+
 impl SecretKey {
     /// Derives a key for a registered application protocol at the given path from the
     /// given seed. Each path element may consist of an index and (possibly empty) tag.
@@ -140,20 +342,22 @@ impl SecretKey {
         zip_number: u16,
         subpath: &[PathElement<'_>],
     ) -> Result<Self, DerivationError> {
-        if context_string.is_empty() || context_string.len() > 252 {
-            return Err(DerivationError::ContextStringInvalid);
-        }
-        if seed.len() < 32 || seed.len() > 252 {
-            return Err(DerivationError::SeedInvalid);
-        }
-
-        let mut xsk = Self::master(context_string, seed)
-            .derive_child(ChildIndex::hardened(u32::from(zip_number)));
+        Ok(ZipRoot::new(context_string, seed, zip_number)?.secret_key(subpath))
+    }
 
-        for elem in subpath {
-            xsk = xsk.derive_child_with_tag(elem.child_index, elem.tag);
-        }
-        Ok(xsk)
+    /// Returns an iterator over the registered keys at each prefix of the given subpath,
+    /// from the root of the ZIP subtree (at the empty prefix) to the key at the full
+    /// subpath.
+    ///
+    /// The arguments are as for [`SecretKey::from_subpath`], and the final key yielded is
+    /// identical to the key it returns.
+    pub fn walk_subpath<'a, 'b>(
+        context_string: &[u8],
+        seed: &[u8],
+        zip_number: u16,
+        subpath: &'a [PathElement<'b>],
+    ) -> Result<SubpathKeys<'a, 'b>, DerivationError> {
+        Ok(ZipRoot::new(context_string, seed, zip_number)?.walk_subpath(subpath))
     }
 
     /// Constructs a key for a registered application protocol from its constituent parts.
@@ -178,7 +382,7 @@ impl SecretKey {
     /// Panics if:
     /// - the context string is empty or longer than 252 bytes.
     /// - the seed is shorter than 32 bytes or longer than 252 bytes.
-    fn master(context_string: &[u8], seed: &[u8]) -> Self {
+    pub(crate) fn master(context_string: &[u8], seed: &[u8]) -> Self {
         with_ikm(context_string, seed, |ikm| Self {
             inner: HardenedOnlyKey::master(ikm),
         })
@@ -213,6 +417,97 @@ impl SecretKey {
         self.inner.ckdh_internal(index, 1, tag)
     }
 
+    /// Derives the child cryptovalue at the given index and (possibly empty) tag, as a
+    /// [`LeafKey`] for expansion into sub-keys.
+    ///
+    /// The wrapped cryptovalue is identical to [`Self::derive_child_cryptovalue`].
+    pub fn derive_child_leaf_key(&self, index: ChildIndex, tag: &[u8]) -> LeafKey {
+        LeafKey::from_cryptovalue(self.derive_child_cryptovalue(index, tag))
+    }
+
+    /// Checks, in constant time, whether `claimed` is the child key of this key at the
+    /// given index and (possibly empty) tag.
+    ///
+    /// The child key is recomputed with [`Self::derive_child_with_tag`], and compared to
+    /// `claimed` without revealing (through timing) which bytes differ.
+    pub fn verify_child_with_tag(
+        &self,
+        index: ChildIndex,
+        tag: &[u8],
+        claimed: &SecretKey,
+    ) -> Choice {
+        self.derive_child_with_tag(index, tag).ct_eq(claimed)
+    }
+
+    /// Checks, in constant time, whether `claimed` is the 64-byte child cryptovalue of
+    /// this key at the given index and (possibly empty) tag.
+    ///
+    /// The cryptovalue is recomputed with [`Self::derive_child_cryptovalue`], and compared
+    /// to `claimed` without revealing (through timing) which bytes differ.
+    pub fn verify_child_cryptovalue(
+        &self,
+        index: ChildIndex,
+        tag: &[u8],
+        claimed: &[u8; 64],
+    ) -> Choice {
+        self.derive_child_cryptovalue(index, tag)[..].ct_eq(&claimed[..])
+    }
+
+    /// Derives the child keys of a parent key at each of the given path elements.
+    ///
+    /// The result is identical to calling [`Self::derive_child_with_tag`] for each
+    /// element, but the children are hashed in parallel SIMD lanes where the platform
+    /// supports it.
+    #[cfg(feature = "alloc")]
+    pub fn derive_children(&self, children: &[PathElement<'_>]) -> Vec<Self> {
+        self.inner
+            .derive_children_with_tags(&batch_inputs(children))
+            .into_iter()
+            .map(|inner| SecretKey { inner })
+            .collect()
+    }
+
+    /// Derives the 64-byte child cryptovalues of a parent key at each of the given path
+    /// elements.
+    ///
+    /// The result is identical to calling [`Self::derive_child_cryptovalue`] for each
+    /// element, but the children are hashed in parallel SIMD lanes where the platform
+    /// supports it.
+    #[cfg(feature = "alloc")]
+    pub fn derive_child_cryptovalues(&self, children: &[PathElement<'_>]) -> Vec<[u8; 64]> {
+        self.inner.ckdh_many(&batch_inputs(children), 1)
+    }
+
+    /// Returns a parallel iterator over the child keys of a parent key at each of the
+    /// given path elements.
+    ///
+    /// The iterator yields the same keys, in the same order, as
+    /// [`Self::derive_children`].
+    #[cfg(feature = "rayon")]
+    pub fn par_derive_children<'a>(
+        &'a self,
+        children: &'a [PathElement<'a>],
+    ) -> impl IndexedParallelIterator<Item = SecretKey> + 'a {
+        children
+            .par_iter()
+            .map(move |elem| self.derive_child_with_tag(elem.child_index, elem.tag))
+    }
+
+    /// Returns a parallel iterator over the 64-byte child cryptovalues of a parent key at
+    /// each of the given path elements.
+    ///
+    /// The iterator yields the same cryptovalues, in the same order, as
+    /// [`Self::derive_child_cryptovalues`].
+    #[cfg(feature = "rayon")]
+    pub fn par_derive_child_cryptovalues<'a>(
+        &'a self,
+        children: &'a [PathElement<'a>],
+    ) -> impl IndexedParallelIterator<Item = [u8; 64]> + 'a {
+        children
+            .par_iter()
+            .map(move |elem| self.derive_child_cryptovalue(elem.child_index, elem.tag))
+    }
+
     /// Returns the key material for this key.
     pub fn data(&self) -> &[u8; 32] {
         self.inner.parts().0
@@ -224,6 +519,214 @@ impl SecretKey {
     }
 }
 
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.inner.zeroize();
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn batch_inputs<'a>(children: &[PathElement<'a>]) -> Vec<(ChildIndex, &'a [u8])> {
+    children
+        .iter()
+        .map(|elem| (elem.child_index, elem.tag))
+        .collect()
+}
+
+/// The root of the registered key subtree for a ZIP, `m_{context} / zip_number'`.
+///
+/// Deriving many keys or cryptovalues from a [`ZipRoot`] avoids repeating master key
+/// generation and input validation for each of them. Every subpath derived from a
+/// [`ZipRoot`] is relative to it, exactly as for [`SecretKey::from_subpath`].
+#[derive(Clone, Debug)]
+pub struct ZipRoot {
+    zip_number: u16,
+    inner: SubtreeRoot,
+}
+
+impl ZipRoot {
+    /// Derives the root of the subtree for the given ZIP number from the given seed.
+    ///
+    /// - `context_string`: an identifier for the context in which this key will be used. It must
+    ///   be globally unique, non-empty, and no more than 252 bytes in length.
+    /// - `seed`: the root seed. Must be between 32 bytes and 252 bytes in length, inclusive.
+    /// - `zip_number`: the number of the ZIP defining the application protocol.
+    pub fn new(
+        context_string: &[u8],
+        seed: &[u8],
+        zip_number: u16,
+    ) -> Result<Self, DerivationError> {
+        check_master_inputs(context_string, seed)?;
+
+        Ok(ZipRoot {
+            zip_number,
+            inner: SubtreeRoot::derive(
+                context_string,
+                seed,
+                ChildIndex::hardened(u32::from(zip_number)),
+            ),
+        })
+    }
+
+    /// Returns the number of the ZIP whose subtree this is the root of.
+    pub fn zip_number(&self) -> u16 {
+        self.zip_number
+    }
+
+    /// Returns the registered key at the root of this subtree.
+    pub fn key(&self) -> &SecretKey {
+        self.inner.key()
+    }
+
+    /// Derives the registered key at the given subpath from this root.
+    pub fn secret_key(&self, subpath: &[PathElement<'_>]) -> SecretKey {
+        self.inner.secret_key(subpath)
+    }
+
+    /// Derives a 64-byte cryptovalue at the given non-empty subpath from this root.
+    pub fn cryptovalue(&self, subpath: &[PathElement<'_>]) -> Result<[u8; 64], DerivationError> {
+        self.inner.cryptovalue(subpath)
+    }
+
+    /// Derives the cryptovalue at the given non-empty subpath from this root, as a
+    /// [`LeafKey`] for expansion into sub-keys.
+    pub fn leaf_key(&self, subpath: &[PathElement<'_>]) -> Result<LeafKey, DerivationError> {
+        self.cryptovalue(subpath).map(LeafKey::from_cryptovalue)
+    }
+
+    /// Returns an iterator over the registered keys at each prefix of the given subpath,
+    /// starting with the key at the root of this subtree.
+    pub fn walk_subpath<'a, 'b>(&self, subpath: &'a [PathElement<'b>]) -> SubpathKeys<'a, 'b> {
+        self.inner.walk_subpath(subpath)
+    }
+}
+
+/// The root of a registered key subtree at an arbitrary hardened index,
+/// `m_{context} / root_index`.
+///
+/// Registered subtrees are normally rooted at a ZIP number, using [`ZipRoot`]. This type
+/// is for subtrees whose root index cannot be expressed as a ZIP number, such as the
+/// private-use subtree at [`ChildIndex::PRIVATE_USE`]. The hardened indices `0'` to
+/// `65535'` are reserved for ZIP numbers, and are rejected by [`SubtreeRoot::new`].
+#[derive(Clone, Debug)]
+pub struct SubtreeRoot {
+    root_index: ChildIndex,
+    key: SecretKey,
+}
+
+impl SubtreeRoot {
+    /// Derives the root of the subtree at the given index from the given seed.
+    ///
+    /// - `context_string`: an identifier for the context in which this key will be used. It must
+    ///   be globally unique, non-empty, and no more than 252 bytes in length.
+    /// - `seed`: the root seed. Must be between 32 bytes and 252 bytes in length, inclusive.
+    /// - `root_index`: the index of the subtree root, as a child of the master key. It must
+    ///   not be a hardened ZIP number (that is, `ChildIndex::hardened(n)` for some `n: u16`).
+    pub fn new(
+        context_string: &[u8],
+        seed: &[u8],
+        root_index: ChildIndex,
+    ) -> Result<Self, SubtreeRootError> {
+        check_master_inputs(context_string, seed)?;
+        if root_index.index() & !(1 << 31) <= u32::from(u16::MAX) {
+            return Err(SubtreeRootError::RootIndexIsZipNumber);
+        }
+
+        Ok(Self::derive(context_string, seed, root_index))
+    }
+
+    /// Derives the root of the subtree at the given index, without checking the inputs.
+    fn derive(context_string: &[u8], seed: &[u8], root_index: ChildIndex) -> Self {
+        SubtreeRoot {
+            root_index,
+            key: SecretKey::master(context_string, seed).derive_child(root_index),
+        }
+    }
+
+    /// Returns the index of this subtree's root, as a child of the master key.
+    pub fn root_index(&self) -> ChildIndex {
+        self.root_index
+    }
+
+    /// Returns the registered key at the root of this subtree.
+    pub fn key(&self) -> &SecretKey {
+        &self.key
+    }
+
+    /// Derives the registered key at the given subpath from this root.
+    pub fn secret_key(&self, subpath: &[PathElement<'_>]) -> SecretKey {
+        let mut xsk = self.key.clone();
+        for elem in subpath {
+            xsk = xsk.derive_child_with_tag(elem.child_index, elem.tag);
+        }
+        xsk
+    }
+
+    /// Derives a 64-byte cryptovalue at the given non-empty subpath from this root.
+    ///
+    /// A non-empty subpath is required, in order to ensure that the resulting full-width
+    /// cryptovalue is within the subtree.
+    pub fn cryptovalue(&self, subpath: &[PathElement<'_>]) -> Result<[u8; 64], DerivationError> {
+        let (last, parent_path) = subpath.split_last().ok_or(DerivationError::SubpathEmpty)?;
+
+        let parent = self.secret_key(parent_path);
+        Ok(parent.derive_child_cryptovalue(last.child_index, last.tag))
+    }
+
+    /// Derives the cryptovalue at the given non-empty subpath from this root, as a
+    /// [`LeafKey`] for expansion into sub-keys.
+    pub fn leaf_key(&self, subpath: &[PathElement<'_>]) -> Result<LeafKey, DerivationError> {
+        self.cryptovalue(subpath).map(LeafKey::from_cryptovalue)
+    }
+
+    /// Returns an iterator over the registered keys at each prefix of the given subpath,
+    /// starting with the key at the root of this subtree.
+    pub fn walk_subpath<'a, 'b>(&self, subpath: &'a [PathElement<'b>]) -> SubpathKeys<'a, 'b> {
+        SubpathKeys {
+            next: Some(self.key.clone()),
+            subpath,
+            depth: 0,
+        }
+    }
+}
+
+/// An iterator over the registered keys at each prefix of a subpath.
+///
+/// Constructed by [`SecretKey::walk_subpath`].
+pub struct SubpathKeys<'a, 'b> {
+    // The key at `subpath[..depth]`, if it has not yet been yielded.
+    next: Option<SecretKey>,
+    subpath: &'a [PathElement<'b>],
+    depth: usize,
+}
+
+impl<'a, 'b> Iterator for SubpathKeys<'a, 'b> {
+    type Item = (&'a [PathElement<'b>], SecretKey);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let xsk = self.next.take()?;
+        let prefix = &self.subpath[..self.depth];
+        if let Some(elem) = self.subpath.get(self.depth) {
+            self.next = Some(xsk.derive_child_with_tag(elem.child_index, elem.tag));
+            self.depth += 1;
+        }
+        Some((prefix, xsk))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.next.is_some() {
+            self.subpath.len() - self.depth + 1
+        } else {
+            0
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for SubpathKeys<'_, '_> {}
+
+impl core::iter::FusedIterator for SubpathKeys<'_, '_> {}
+
 /// Derives a 64-byte cryptovalue (for use as key material for example), for a registered
 /// application protocol at the given non-empty subpath from the given seed. Each subpath element
 /// may consist of an index and a (possibly empty) tag.
@@ -242,25 +745,68 @@ pub fn cryptovalue_from_subpath(
     zip_number: u16,
     subpath: &[PathElement<'_>],
 ) -> Result<[u8; 64], DerivationError> {
-    if context_string.is_empty() || context_string.len() > 252 {
-        return Err(DerivationError::ContextStringInvalid);
-    }
-    if seed.len() < 32 || seed.len() > 252 {
-        return Err(DerivationError::SeedInvalid);
+    ZipRoot::new(context_string, seed, zip_number)?.cryptovalue(subpath)
+}
+
+/// Returns a parallel iterator that derives a 64-byte cryptovalue for each `(seed, subpath)`
+/// input, for a registered application protocol with the given context string and ZIP
+/// number.
+///
+/// Each item is identical to the result of calling [`cryptovalue_from_subpath`] with the
+/// corresponding input, and items are yielded in input order. Inputs are processed
+/// independently, so an invalid seed or empty subpath only causes the corresponding item
+/// to be an error.
+#[cfg(feature = "rayon")]
+pub fn par_cryptovalues_from_subpaths<'a, 'p, S, P>(
+    context_string: &'a [u8],
+    zip_number: u16,
+    inputs: &'a [(S, P)],
+) -> impl IndexedParallelIterator<Item = Result<[u8; 64], DerivationError>> + 'a
+where
+    S: AsRef<[u8]> + Sync,
+    P: AsRef<[PathElement<'p>]> + Sync,
+{
+    inputs.par_iter().map(move |(seed, subpath)| {
+        cryptovalue_from_subpath(context_string, seed.as_ref(), zip_number, subpath.as_ref())
+    })
+}
+
+/// [`proptest`] strategies for registered derivation inputs.
+#[cfg(all(feature = "alloc", any(test, feature = "proptest")))]
+pub mod testing {
+    use alloc::vec::Vec;
+    use core::ops::RangeInclusive;
+
+    use proptest::{collection::vec, prelude::*};
+
+    use super::OwnedPathElement;
+    use crate::testing::arb_child_index;
+
+    /// Returns a strategy for generating valid context strings (1 to 252 bytes,
+    /// inclusive).
+    pub fn arb_context_string() -> impl Strategy<Value = Vec<u8>> {
+        vec(any::<u8>(), 1..=252)
     }
-    // We can't use NonEmpty because it requires allocation.
-    if subpath.is_empty() {
-        return Err(DerivationError::SubpathEmpty);
+
+    /// Returns a strategy for generating tags of up to 512 bytes.
+    ///
+    /// ZIP 32 does not limit the length of tags; this bound keeps generated inputs small
+    /// while still covering tags that span several BLAKE2b blocks.
+    pub fn arb_tag() -> impl Strategy<Value = Vec<u8>> {
+        vec(any::<u8>(), 0..=512)
     }
 
-    let mut xsk = SecretKey::master(context_string, seed)
-        .derive_child(ChildIndex::hardened(u32::from(zip_number)));
+    /// Returns a strategy for generating path elements with arbitrary child indices and
+    /// tags. Half of the generated elements have an empty tag.
+    pub fn arb_path_element() -> impl Strategy<Value = OwnedPathElement> {
+        (arb_child_index(), prop_oneof![Just(Vec::new()), arb_tag()])
+            .prop_map(|(child_index, tag)| OwnedPathElement::new(child_index, tag))
+    }
 
-    for elem in subpath.iter().take(subpath.len() - 1) {
-        xsk = xsk.derive_child_with_tag(elem.child_index, elem.tag);
+    /// Returns a strategy for generating subpaths whose length is within `len`.
+    pub fn arb_subpath(len: RangeInclusive<usize>) -> impl Strategy<Value = Vec<OwnedPathElement>> {
+        vec(arb_path_element(), len)
     }
-    let elem = subpath.last().expect("nonempty");
-    Ok(xsk.derive_child_cryptovalue(elem.child_index, elem.tag))
 }
 
 #[cfg(test)]
@@ -273,6 +819,12 @@ mod tests {
 
     use super::{cryptovalue_from_subpath, ChildIndex, DerivationError, SecretKey};
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn batched_derivation_matches_context() {
+        crate::hardened_only::check_ckdh_many::<super::Registered>();
+    }
+
     #[test]
     fn test_cryptovalue_from_empty_subpath_errors() {
         assert_eq!(
@@ -420,6 +972,15 @@ mod tests {
             assert_eq!(sk.data(), &tv.sk);
             assert_eq!(sk.chain_code().as_bytes(), &tv.c);
 
+            let (prefix, walked) =
+                SecretKey::walk_subpath(tv.context_string, &tv.seed, tv.zip_number, &subpath)
+                    .unwrap()
+                    .last()
+                    .unwrap();
+            assert_eq!(prefix.len(), subpath.len());
+            assert_eq!(walked.data(), &tv.sk);
+            assert_eq!(walked.chain_code().as_bytes(), &tv.c);
+
             let fw = (!subpath.is_empty()).then(|| {
                 cryptovalue_from_subpath(tv.context_string, &tv.seed, tv.zip_number, &subpath)
                     .unwrap()
@@ -431,4 +992,496 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn walk_subpath_yields_every_prefix() {
+        let context_string = b"Zcash test vectors";
+        let seed = [0; 32];
+        let subpath = [
+            PathElement::new(ChildIndex::hardened(1), b"one"),
+            PathElement::new(ChildIndex::hardened(2), &[]),
+        ];
+
+        let walk = SecretKey::walk_subpath(context_string, &seed, 7, &subpath).unwrap();
+        assert_eq!(walk.len(), 3);
+        for (depth, (prefix, sk)) in walk.enumerate() {
+            assert_eq!(prefix.len(), depth);
+            let expected = SecretKey::from_subpath(context_string, &seed, 7, prefix).unwrap();
+            assert_eq!(sk.data(), expected.data());
+            assert_eq!(sk.chain_code(), expected.chain_code());
+        }
+
+        assert_eq!(
+            SecretKey::walk_subpath(&[], &seed, 7, &subpath).err(),
+            Some(DerivationError::ContextStringInvalid)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn batch_derivation_matches_sequential() {
+        let parent = SecretKey::from_subpath(b"Zcash test vectors", &[0; 32], 1, &[]).unwrap();
+        let tags: [&[u8]; 3] = [&[], b"tag", &[0xff; 300]];
+        let children = (0..20)
+            .map(|i| PathElement::new(ChildIndex::hardened(i), tags[i as usize % 3]))
+            .collect::<alloc::vec::Vec<_>>();
+
+        let keys = parent.derive_children(&children);
+        let cryptovalues = parent.derive_child_cryptovalues(&children);
+        assert_eq!(keys.len(), children.len());
+        assert_eq!(cryptovalues.len(), children.len());
+        for ((elem, key), cv) in children.iter().zip(&keys).zip(&cryptovalues) {
+            let expected = parent.derive_child_with_tag(elem.child_index(), elem.tag());
+            assert_eq!(key.data(), expected.data());
+            assert_eq!(key.chain_code(), expected.chain_code());
+            assert_eq!(
+                cv,
+                &parent.derive_child_cryptovalue(elem.child_index(), elem.tag())
+            );
+        }
+
+        assert!(parent.derive_children(&[]).is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_derivation_matches_sequential() {
+        use alloc::vec::Vec;
+        use rayon::prelude::*;
+
+        use super::par_cryptovalues_from_subpaths;
+
+        let context = b"Zcash test vectors";
+        let inputs = (0u8..50)
+            .map(|i| {
+                let seed = [i; 32];
+                let subpath = (0..u32::from(i % 4))
+                    .map(|j| PathElement::new(ChildIndex::hardened(j), b"tag"))
+                    .collect::<Vec<_>>();
+                (seed, subpath)
+            })
+            .collect::<Vec<_>>();
+
+        let parallel = par_cryptovalues_from_subpaths(context, 7, &inputs).collect::<Vec<_>>();
+        assert_eq!(parallel.len(), inputs.len());
+        for ((seed, subpath), actual) in inputs.iter().zip(parallel) {
+            assert_eq!(actual, cryptovalue_from_subpath(context, seed, 7, subpath));
+        }
+
+        let parent = SecretKey::from_subpath(context, &[0; 32], 7, &[]).unwrap();
+        let children = (0..50)
+            .map(|i| PathElement::new(ChildIndex::hardened(i), &[]))
+            .collect::<Vec<_>>();
+        let keys = parent.par_derive_children(&children).collect::<Vec<_>>();
+        for (key, expected) in keys.iter().zip(parent.derive_children(&children)) {
+            assert_eq!(key.data(), expected.data());
+            assert_eq!(key.chain_code(), expected.chain_code());
+        }
+        assert_eq!(
+            parent
+                .par_derive_child_cryptovalues(&children)
+                .collect::<Vec<_>>(),
+            parent.derive_child_cryptovalues(&children)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_path_elements() {
+        use alloc::vec::Vec;
+
+        use super::{OwnedPathElement, SubpathBuilder};
+
+        let subpath = SubpathBuilder::new()
+            .child(ChildIndex::hardened(1))
+            .child_with_tag(ChildIndex::hardened(2), b"tag".to_vec())
+            .child_with_tag(ChildIndex::hardened(3), [7; 300])
+            .build();
+        assert_eq!(subpath.len(), 3);
+        assert_eq!(subpath[0].child_index(), ChildIndex::hardened(1));
+        assert_eq!(subpath[0].tag(), b"");
+        assert_eq!(subpath[1].tag(), b"tag");
+
+        let borrowed = subpath
+            .iter()
+            .map(OwnedPathElement::as_borrowed)
+            .collect::<Vec<_>>();
+        let expected = [
+            PathElement::new(ChildIndex::hardened(1), &[]),
+            PathElement::new(ChildIndex::hardened(2), b"tag"),
+            PathElement::new(ChildIndex::hardened(3), &[7; 300]),
+        ];
+        let context = b"Zcash test vectors";
+        assert_eq!(
+            cryptovalue_from_subpath(context, &[0; 32], 7, &borrowed),
+            cryptovalue_from_subpath(context, &[0; 32], 7, &expected),
+        );
+
+        let round_trip = OwnedPathElement::from(PathElement::from(&subpath[1]));
+        assert_eq!(round_trip.child_index(), ChildIndex::hardened(2));
+        assert_eq!(round_trip.tag(), b"tag");
+
+        assert!(SubpathBuilder::new().build().is_empty());
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_path_elements_are_hardened() {
+        use ::arbitrary::{Arbitrary, Unstructured};
+
+        for data in [[0x00; 1024], [0x5a; 1024], [0xff; 1024]] {
+            let elem = PathElement::arbitrary_take_rest(Unstructured::new(&data)).unwrap();
+            assert!(elem.child_index().index() >= (1 << 31));
+        }
+    }
+
+    #[test]
+    fn long_tags() {
+        let context = b"Zcash test vectors";
+        let seed = [0; 32];
+        let root = SecretKey::from_subpath(context, &seed, 7, &[]).unwrap();
+
+        // ZIP 32 does not limit the length of tags.
+        let long = [0xff; 1 << 16];
+        let subpath = [PathElement::new(ChildIndex::hardened(1), &long)];
+        let key = SecretKey::from_subpath(context, &seed, 7, &subpath).unwrap();
+        assert_eq!(
+            key.data(),
+            root.derive_child_with_tag(ChildIndex::hardened(1), &long)
+                .data()
+        );
+        assert_eq!(
+            cryptovalue_from_subpath(context, &seed, 7, &subpath).unwrap(),
+            root.derive_child_cryptovalue(ChildIndex::hardened(1), &long),
+        );
+        let shorter = SecretKey::from_subpath(
+            context,
+            &seed,
+            7,
+            &[PathElement::new(ChildIndex::hardened(1), &long[1..])],
+        )
+        .unwrap();
+        assert_ne!(key.data(), shorter.data());
+    }
+
+    #[test]
+    fn trait_impls() {
+        use alloc::format;
+        use subtle::ConstantTimeEq;
+        use zeroize::Zeroize;
+
+        let context = b"Zcash test vectors";
+        let a = SecretKey::from_subpath(context, &[0; 32], 7, &[]).unwrap();
+        let b = SecretKey::from_subpath(context, &[1; 32], 7, &[]).unwrap();
+        assert!(bool::from(a.ct_eq(&a.clone())));
+        assert!(!bool::from(a.ct_eq(&b)));
+
+        let debug = format!("{:?}", a);
+        assert_eq!(debug, "SecretKey { .. }");
+
+        let mut zeroized = a;
+        zeroized.zeroize();
+        assert_eq!(zeroized.data(), &[0; 32]);
+        assert_eq!(zeroized.chain_code().as_bytes(), &[0; 32]);
+
+        let elem = PathElement::new(ChildIndex::hardened(1), b"tag");
+        let copy = elem;
+        assert_eq!(elem, copy);
+        assert_ne!(elem, PathElement::new(ChildIndex::hardened(1), b"other"));
+        assert_ne!(elem, PathElement::new(ChildIndex::hardened(2), b"tag"));
+        assert_eq!(
+            format!("{:?}", elem),
+            "PathElement { child_index: ChildIndex(2147483649), tag: [116, 97, 103] }"
+        );
+
+        #[cfg(feature = "std")]
+        {
+            let set = [
+                elem,
+                copy,
+                PathElement::new(ChildIndex::hardened(2), b"tag"),
+            ]
+            .into_iter()
+            .collect::<std::collections::HashSet<_>>();
+            assert_eq!(set.len(), 2);
+        }
+    }
+
+    #[test]
+    fn zip_root_matches_from_subpath() {
+        use super::ZipRoot;
+
+        let context = b"Zcash test vectors";
+        let seed = [0; 32];
+        let root = ZipRoot::new(context, &seed, 7).unwrap();
+        assert_eq!(root.zip_number(), 7);
+        assert_eq!(
+            root.key().data(),
+            SecretKey::from_subpath(context, &seed, 7, &[])
+                .unwrap()
+                .data()
+        );
+
+        let subpaths: [&[PathElement<'_>]; 3] = [
+            &[PathElement::new(ChildIndex::hardened(1), &[])],
+            &[PathElement::new(ChildIndex::hardened(1), b"tag")],
+            &[
+                PathElement::new(ChildIndex::hardened(1), &[]),
+                PathElement::new(ChildIndex::hardened(2), b"tag"),
+            ],
+        ];
+        for subpath in subpaths {
+            let expected = SecretKey::from_subpath(context, &seed, 7, subpath).unwrap();
+            let actual = root.secret_key(subpath);
+            assert_eq!(actual.data(), expected.data());
+            assert_eq!(actual.chain_code(), expected.chain_code());
+            assert_eq!(
+                root.cryptovalue(subpath),
+                cryptovalue_from_subpath(context, &seed, 7, subpath)
+            );
+            let (_, last) = root.walk_subpath(subpath).last().unwrap();
+            assert_eq!(last.data(), expected.data());
+        }
+
+        assert_eq!(root.cryptovalue(&[]), Err(DerivationError::SubpathEmpty));
+        assert_eq!(
+            ZipRoot::new(&[], &seed, 7).err(),
+            Some(DerivationError::ContextStringInvalid)
+        );
+        assert_eq!(
+            ZipRoot::new(context, &[0; 31], 7).err(),
+            Some(DerivationError::SeedInvalid)
+        );
+    }
+
+    #[test]
+    fn leaf_keys_match_cryptovalues() {
+        use super::{SubtreeRoot, ZipRoot};
+
+        let context = b"Zcash test vectors";
+        let seed = [0; 32];
+        let subpath = [
+            PathElement::new(ChildIndex::hardened(2), &[]),
+            PathElement::new(ChildIndex::hardened(2), b"trans"),
+        ];
+
+        let root = ZipRoot::new(context, &seed, 1).unwrap();
+        assert_eq!(
+            root.leaf_key(&subpath).unwrap().cryptovalue(),
+            &root.cryptovalue(&subpath).unwrap(),
+        );
+        assert!(root.leaf_key(&[]).is_err());
+
+        let root = SubtreeRoot::new(context, &seed, ChildIndex::PRIVATE_USE).unwrap();
+        assert_eq!(
+            root.leaf_key(&subpath).unwrap().cryptovalue(),
+            &root.cryptovalue(&subpath).unwrap(),
+        );
+
+        let xsk = SecretKey::from_subpath(context, &seed, 1, &subpath[..1]).unwrap();
+        assert_eq!(
+            xsk.derive_child_leaf_key(ChildIndex::hardened(2), b"trans")
+                .cryptovalue(),
+            &xsk.derive_child_cryptovalue(ChildIndex::hardened(2), b"trans"),
+        );
+    }
+
+    #[test]
+    fn private_use_subtree() {
+        use super::{SubtreeRoot, SubtreeRootError};
+
+        // Generated by `test-vectors/generators/registered_private_use.py`.
+        let context = b"Zcash test vectors";
+        let seed = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+            0x1c, 0x1d, 0x1e, 0x1f,
+        ];
+        let root = SubtreeRoot::new(context, &seed, ChildIndex::PRIVATE_USE).unwrap();
+        assert_eq!(root.root_index(), ChildIndex::PRIVATE_USE);
+        assert_eq!(
+            root.key().data(),
+            &[
+                0x9e, 0x8f, 0x74, 0xbc, 0xf7, 0x0e, 0x55, 0xd6, 0xc7, 0xb4, 0x66, 0x9d, 0xa8, 0x7f,
+                0xd8, 0xc1, 0x83, 0x46, 0x18, 0xc5, 0x2a, 0x68, 0x64, 0x9b, 0x93, 0xef, 0xa4, 0xe9,
+                0xf4, 0xb2, 0x4c, 0xc8,
+            ]
+        );
+        assert_eq!(
+            root.key().chain_code().as_bytes(),
+            &[
+                0x54, 0xb8, 0xf8, 0x73, 0xfb, 0x68, 0x31, 0xa8, 0x83, 0xa2, 0x6a, 0x76, 0xb2, 0xd7,
+                0x2a, 0x7e, 0x92, 0xbd, 0x20, 0x96, 0x91, 0x79, 0x61, 0x6b, 0x97, 0xa1, 0xd2, 0x85,
+                0x6f, 0x3e, 0xe6, 0x6f,
+            ]
+        );
+
+        let subpath = [PathElement::new(ChildIndex::hardened(1), b"tag")];
+        let child = root.secret_key(&subpath);
+        assert_eq!(
+            child.data(),
+            &[
+                0x56, 0xb0, 0xfe, 0x60, 0x6f, 0x08, 0x8c, 0x2d, 0x60, 0x7d, 0x4b, 0x14, 0xb9, 0x59,
+                0x39, 0x19, 0x9e, 0x69, 0x88, 0x82, 0x3d, 0xc5, 0x1b, 0xdd, 0x5f, 0xf4, 0xc7, 0x36,
+                0x90, 0xb8, 0x1d, 0xfb,
+            ]
+        );
+        assert_eq!(
+            child.chain_code().as_bytes(),
+            &[
+                0x77, 0x71, 0xea, 0x0b, 0x02, 0x04, 0x07, 0x3e, 0x1e, 0x8e, 0xbc, 0x67, 0x73, 0x96,
+                0x58, 0x17, 0x60, 0xb6, 0xb9, 0xc7, 0x08, 0xbe, 0xa7, 0xc9, 0xbb, 0x0f, 0x96, 0x8a,
+                0x13, 0xcb, 0xbf, 0xcb,
+            ]
+        );
+        assert_eq!(
+            root.cryptovalue(&subpath).unwrap(),
+            [
+                0x30, 0x31, 0x3a, 0x16, 0x4e, 0x67, 0xa5, 0x1b, 0x6b, 0x06, 0xb2, 0x69, 0x95, 0xea,
+                0x1e, 0x98, 0xe3, 0x94, 0x28, 0x3c, 0x41, 0xfa, 0x76, 0x31, 0x6b, 0x85, 0x14, 0xfd,
+                0x25, 0x3d, 0xa4, 0xe8, 0x8b, 0xa4, 0xaa, 0x02, 0x15, 0xf4, 0x68, 0xdc, 0xf9, 0xe3,
+                0x03, 0x69, 0x44, 0xfc, 0xb6, 0x38, 0xcd, 0xcd, 0xc9, 0x2a, 0x52, 0xe7, 0xc7, 0x53,
+                0xb4, 0x37, 0x68, 0x03, 0xda, 0xc9, 0xca, 0x6e,
+            ]
+        );
+        assert_eq!(root.cryptovalue(&[]), Err(DerivationError::SubpathEmpty));
+
+        // Subtrees rooted at hardened ZIP numbers must be derived with `ZipRoot`.
+        for index in [0, 1, u32::from(u16::MAX)] {
+            assert_eq!(
+                SubtreeRoot::new(context, &seed, ChildIndex::hardened(index)).err(),
+                Some(SubtreeRootError::RootIndexIsZipNumber)
+            );
+        }
+        assert!(SubtreeRoot::new(context, &seed, ChildIndex::hardened(1 << 16)).is_ok());
+        assert_eq!(
+            SubtreeRoot::new(context, &seed[..31], ChildIndex::PRIVATE_USE).err(),
+            Some(SubtreeRootError::Derivation(DerivationError::SeedInvalid))
+        );
+    }
+
+    #[test]
+    fn verify_children() {
+        use super::ZipRoot;
+
+        let root = ZipRoot::new(b"Zcash test vectors", &[0; 32], 7).unwrap();
+        let parent = root.key();
+        let index = ChildIndex::hardened(1);
+
+        let cv = root
+            .cryptovalue(&[PathElement::new(index, b"tag")])
+            .unwrap();
+        assert!(bool::from(
+            parent.verify_child_cryptovalue(index, b"tag", &cv)
+        ));
+        assert!(!bool::from(
+            parent.verify_child_cryptovalue(index, b"other", &cv)
+        ));
+        assert!(!bool::from(parent.verify_child_cryptovalue(
+            ChildIndex::hardened(2),
+            b"tag",
+            &cv
+        )));
+        let mut tampered = cv;
+        tampered[63] ^= 1;
+        assert!(!bool::from(
+            parent.verify_child_cryptovalue(index, b"tag", &tampered)
+        ));
+
+        let child = root.secret_key(&[PathElement::new(index, b"tag")]);
+        assert!(bool::from(
+            parent.verify_child_with_tag(index, b"tag", &child)
+        ));
+        assert!(!bool::from(parent.verify_child_with_tag(
+            index,
+            &[],
+            &child
+        )));
+        // A child key is not accepted as a cryptovalue at the same path, or vice versa.
+        let mut child_bytes = [0; 64];
+        child_bytes[..32].copy_from_slice(child.data());
+        child_bytes[32..].copy_from_slice(child.chain_code().as_bytes());
+        assert!(!bool::from(parent.verify_child_cryptovalue(
+            index,
+            b"tag",
+            &child_bytes
+        )));
+        // The parent is not its own child.
+        assert!(!bool::from(
+            parent.verify_child_with_tag(index, b"tag", parent)
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    mod properties {
+        use alloc::vec::Vec;
+
+        use proptest::prelude::*;
+
+        use crate::{
+            fingerprint::testing::arb_seed,
+            registered::{
+                testing::{arb_context_string, arb_subpath},
+                OwnedPathElement, PathElement, SecretKey, ZipRoot,
+            },
+        };
+
+        fn borrow(subpath: &[OwnedPathElement]) -> Vec<PathElement<'_>> {
+            subpath.iter().map(OwnedPathElement::as_borrowed).collect()
+        }
+
+        proptest! {
+            #[test]
+            fn derivation_is_deterministic(
+                context in arb_context_string(),
+                seed in arb_seed(),
+                zip_number in any::<u16>(),
+                subpath in arb_subpath(1..=3),
+            ) {
+                let subpath = borrow(&subpath);
+                let a = SecretKey::from_subpath(&context, &seed, zip_number, &subpath).unwrap();
+                let b = SecretKey::from_subpath(&context, &seed, zip_number, &subpath).unwrap();
+                assert_eq!(a.data(), b.data());
+                assert_eq!(a.chain_code(), b.chain_code());
+
+                let root = ZipRoot::new(&context, &seed, zip_number).unwrap();
+                assert_eq!(
+                    root.cryptovalue(&subpath),
+                    super::cryptovalue_from_subpath(&context, &seed, zip_number, &subpath)
+                );
+            }
+
+            #[test]
+            fn subpaths_compose(
+                context in arb_context_string(),
+                seed in arb_seed(),
+                zip_number in any::<u16>(),
+                prefix in arb_subpath(0..=2),
+                suffix in arb_subpath(1..=2),
+            ) {
+                let prefix = borrow(&prefix);
+                let suffix = borrow(&suffix);
+                let full = prefix.iter().chain(&suffix).copied().collect::<Vec<_>>();
+                let root = ZipRoot::new(&context, &seed, zip_number).unwrap();
+
+                // Deriving along the suffix from the key at the prefix reaches the key at
+                // the full subpath.
+                let mut key = root.secret_key(&prefix);
+                for elem in &suffix {
+                    key = key.derive_child_with_tag(elem.child_index(), elem.tag());
+                }
+                let expected = SecretKey::from_subpath(&context, &seed, zip_number, &full).unwrap();
+                assert_eq!(key.data(), expected.data());
+                assert_eq!(key.chain_code(), expected.chain_code());
+
+                // The cryptovalue at the full subpath is derived from its parent key.
+                let (last, parent_path) = full.split_last().unwrap();
+                let parent = root.secret_key(parent_path);
+                assert_eq!(
+                    root.cryptovalue(&full).unwrap(),
+                    parent.derive_child_cryptovalue(last.child_index(), last.tag())
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,163 @@
+//! Sapling diversifier derivation.
+//!
+//! Sapling diversifiers are derived from a [`DiversifierIndex`] by encrypting it with
+//! FF1-AES256 under a diversifier key `dk`. Because FF1 is a format-preserving
+//! permutation of the 88-bit index space, a diversifier can be decrypted back to the
+//! index that produced it.
+//!
+//! This module does not check whether a diversifier is valid (that is, whether it maps
+//! to a point on the Jubjub curve); callers that need valid diversifiers must perform
+//! that check themselves, and try the next index if it fails.
+//!
+//! Defined in [ZIP 32: Sapling diversifier derivation][saplingdiversifier].
+//!
+//! [saplingdiversifier]: https://zips.z.cash/zip-0032#sapling-diversifier-derivation
+
+use core::fmt;
+
+use aes::Aes256;
+use fpe::ff1::{BinaryNumeralString, FF1};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::DiversifierIndex;
+
+/// A Sapling diversifier key, used to derive diversifiers from diversifier indices.
+///
+/// Defined in [ZIP 32: Sapling diversifier derivation][saplingdiversifier].
+///
+/// [saplingdiversifier]: https://zips.z.cash/zip-0032#sapling-diversifier-derivation
+#[derive(Clone)]
+pub struct DiversifierKey([u8; 32]);
+
+impl fmt::Debug for DiversifierKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The key is not rendered.
+        f.debug_struct("DiversifierKey").finish_non_exhaustive()
+    }
+}
+
+impl ConstantTimeEq for DiversifierKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl Drop for DiversifierKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl DiversifierKey {
+    /// Constructs a diversifier key from its byte representation.
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        DiversifierKey(key)
+    }
+
+    /// Returns the byte representation of this diversifier key.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    fn ff1(&self) -> FF1<Aes256> {
+        FF1::<Aes256>::new(&self.0, 2).expect("radix 2 is valid")
+    }
+
+    /// Returns the diversifier `d_j = FF1-AES256(dk, j)` for the given index.
+    ///
+    /// The returned diversifier may not be valid for use in a Sapling payment address.
+    pub fn diversifier(&self, j: DiversifierIndex) -> [u8; 11] {
+        let enc = self
+            .ff1()
+            .encrypt(&[], &BinaryNumeralString::from_bytes_le(j.as_bytes()))
+            .expect("88-bit numeral string is within FF1 length bounds");
+        enc.to_bytes_le()
+            .as_slice()
+            .try_into()
+            .expect("FF1 preserves length")
+    }
+
+    /// Returns the diversifier index `j` for which `self.diversifier(j) == d`.
+    pub fn diversifier_index(&self, d: &[u8; 11]) -> DiversifierIndex {
+        let dec = self
+            .ff1()
+            .decrypt(&[], &BinaryNumeralString::from_bytes_le(d))
+            .expect("88-bit numeral string is within FF1 length bounds");
+        let j: [u8; 11] = dec
+            .to_bytes_le()
+            .as_slice()
+            .try_into()
+            .expect("FF1 preserves length");
+        DiversifierIndex::from(j)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiversifierKey;
+    use crate::DiversifierIndex;
+
+    struct TestVector {
+        j: u128,
+        d: [u8; 11],
+    }
+
+    // Generated by `test-vectors/generators/sapling_diversifier.py`, which implements
+    // FF1-AES256 independently and checks it against the NIST SP 800-38G samples.
+    const DK: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d,
+        0x1e, 0x1f,
+    ];
+
+    const TEST_VECTORS: &[TestVector] = &[
+        TestVector {
+            j: 0,
+            d: [
+                0xd7, 0x39, 0xcc, 0xc2, 0xb8, 0x4d, 0x5d, 0x1a, 0xe5, 0x4a, 0x95,
+            ],
+        },
+        TestVector {
+            j: 1,
+            d: [
+                0x3d, 0xbe, 0x36, 0xc4, 0xf6, 0xe1, 0x5e, 0x96, 0x4a, 0xec, 0x24,
+            ],
+        },
+        TestVector {
+            j: 0x00ff_ffff_ffff_ffff_ffff_ffff,
+            d: [
+                0x1e, 0x85, 0x97, 0x47, 0x0b, 0xe5, 0x45, 0x5a, 0x58, 0x4b, 0xef,
+            ],
+        },
+    ];
+
+    #[test]
+    fn test_vectors() {
+        let dk = DiversifierKey::from_bytes(DK);
+        for tv in TEST_VECTORS {
+            let j = DiversifierIndex::try_from(tv.j).unwrap();
+            assert_eq!(dk.diversifier(j), tv.d);
+            assert_eq!(dk.diversifier_index(&tv.d), j);
+        }
+    }
+
+    #[test]
+    fn debug_does_not_render_key() {
+        use alloc::format;
+
+        let dk = DiversifierKey::from_bytes(DK);
+        assert_eq!(format!("{:?}", dk), "DiversifierKey { .. }");
+    }
+
+    #[test]
+    fn diversifier_round_trip() {
+        let dk = DiversifierKey::from_bytes([7; 32]);
+        let mut j = DiversifierIndex::new();
+        for _ in 0..100 {
+            let d = dk.diversifier(j);
+            assert_eq!(dk.diversifier_index(&d), j);
+            j.increment().unwrap();
+        }
+    }
+}
@@ -0,0 +1,539 @@
+//! Generation of ZIP 32 test vectors.
+//!
+//! This module allows this crate to act as a reference implementation for other ZIP 32
+//! implementations, by generating test vectors from caller-chosen inputs. Vectors are
+//! emitted as JSON, in the format produced by the [zcash-test-vectors] project: a JSON
+//! array whose first element describes the source of the vectors, whose second element
+//! holds the comma-separated field names, and whose remaining elements are the vectors
+//! themselves, with byte strings encoded as hex.
+//!
+//! ```
+//! use zip32::{registered::PathElement, test_vectors::RegisteredVectors, ChildIndex};
+//!
+//! let mut vectors = RegisteredVectors::new();
+//! vectors
+//!     .add(
+//!         b"Zcash test vectors",
+//!         &[0; 252],
+//!         1,
+//!         &[PathElement::new(ChildIndex::hardened(2), &[0xff; 252])],
+//!     )
+//!     .unwrap();
+//! let json = vectors.to_json("Long seed and tag");
+//! ```
+//!
+//! [zcash-test-vectors]: https://github.com/zcash-hackworks/zcash-test-vectors
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{
+    arbitrary,
+    fingerprint::SeedFingerprint,
+    registered::{check_master_inputs, DerivationError, PathElement, ZipRoot},
+    with_ikm, ChildIndex,
+};
+
+/// Seed fingerprint test vectors.
+///
+/// The fields of each vector are `root_seed`, `fingerprint` and `fingerprint_str`.
+#[derive(Debug, Default)]
+pub struct SeedFingerprintVectors {
+    rows: Vec<String>,
+}
+
+impl SeedFingerprintVectors {
+    /// Constructs an empty set of test vectors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the test vector for the given seed.
+    ///
+    /// Returns [`DerivationError::SeedInvalid`] if the seed is shorter than 32 bytes or
+    /// longer than 252 bytes.
+    pub fn add(&mut self, seed: &[u8]) -> Result<(), DerivationError> {
+        let fp = SeedFingerprint::from_seed(seed).ok_or(DerivationError::SeedInvalid)?;
+        let mut row = String::new();
+        write!(row, "[{}, {}, \"{}\"]", Hex(seed), Hex(&fp.to_bytes()), fp)
+            .expect("writing to a String cannot fail");
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Returns these test vectors as JSON, with the given description of their source.
+    pub fn to_json(&self, source: &str) -> String {
+        render(
+            source,
+            "root_seed, fingerprint, fingerprint_str",
+            &self.rows,
+        )
+    }
+}
+
+/// Ad-hoc ("arbitrary") key derivation test vectors.
+///
+/// The fields of each vector are `context_string`, `seed`, `ikm`, `path`, `sk` and `c`.
+/// The `ikm` field holds the input to master key generation for vectors at the empty
+/// path, and is `null` otherwise.
+#[derive(Debug, Default)]
+pub struct ArbitraryVectors {
+    rows: Vec<String>,
+}
+
+impl ArbitraryVectors {
+    /// Constructs an empty set of test vectors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the test vector for the key at the given path.
+    ///
+    /// Returns an error if the context string or seed is not a valid input to
+    /// [`arbitrary::SecretKey::from_path`].
+    pub fn add(
+        &mut self,
+        context_string: &[u8],
+        seed: &[u8],
+        path: &[ChildIndex],
+    ) -> Result<(), DerivationError> {
+        check_master_inputs(context_string, seed)?;
+
+        let sk = arbitrary::SecretKey::from_path(context_string, seed, path);
+        let mut row = String::new();
+        write!(row, "[{}, {}, ", Hex(context_string), Hex(seed))
+            .expect("writing to a String cannot fail");
+        if path.is_empty() {
+            with_ikm(context_string, seed, |ikm| {
+                write!(row, "{}", Hex(&ikm.concat()))
+            })
+        } else {
+            write!(row, "null")
+        }
+        .expect("writing to a String cannot fail");
+        write!(
+            row,
+            ", [{}], {}, {}]",
+            Indices(path),
+            Hex(sk.data()),
+            Hex(sk.chain_code().as_bytes())
+        )
+        .expect("writing to a String cannot fail");
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Returns these test vectors as JSON, with the given description of their source.
+    pub fn to_json(&self, source: &str) -> String {
+        render(source, "context_string, seed, ikm, path, sk, c", &self.rows)
+    }
+}
+
+/// Registered key derivation test vectors.
+///
+/// The fields of each vector are `context_string`, `seed`, `zip_number`, `subpath`, `sk`,
+/// `c` and `full_width`. Each element of `subpath` is a pair of a child index and a tag.
+/// The `full_width` field holds the 64-byte cryptovalue at the subpath, and is `null` for
+/// the empty subpath.
+#[derive(Debug, Default)]
+pub struct RegisteredVectors {
+    rows: Vec<String>,
+}
+
+impl RegisteredVectors {
+    /// Constructs an empty set of test vectors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the test vector for the key and cryptovalue at the given subpath.
+    ///
+    /// Returns an error if the inputs are not valid inputs to
+    /// [`registered::SecretKey::from_subpath`].
+    ///
+    /// [`registered::SecretKey::from_subpath`]: crate::registered::SecretKey::from_subpath
+    pub fn add(
+        &mut self,
+        context_string: &[u8],
+        seed: &[u8],
+        zip_number: u16,
+        subpath: &[PathElement<'_>],
+    ) -> Result<(), DerivationError> {
+        let root = ZipRoot::new(context_string, seed, zip_number)?;
+        let sk = root.secret_key(subpath);
+
+        let mut row = String::new();
+        write!(
+            row,
+            "[{}, {}, {}, [",
+            Hex(context_string),
+            Hex(seed),
+            zip_number
+        )
+        .expect("writing to a String cannot fail");
+        for (i, elem) in subpath.iter().enumerate() {
+            if i > 0 {
+                row.push_str(", ");
+            }
+            write!(row, "[{}, {}]", elem.child_index().index(), Hex(elem.tag()))
+                .expect("writing to a String cannot fail");
+        }
+        write!(
+            row,
+            "], {}, {}, ",
+            Hex(sk.data()),
+            Hex(sk.chain_code().as_bytes())
+        )
+        .expect("writing to a String cannot fail");
+        if subpath.is_empty() {
+            row.push_str("null]");
+        } else {
+            let cryptovalue = root.cryptovalue(subpath)?;
+            write!(row, "{}]", Hex(&cryptovalue)).expect("writing to a String cannot fail");
+        }
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Returns these test vectors as JSON, with the given description of their source.
+    pub fn to_json(&self, source: &str) -> String {
+        render(
+            source,
+            "context_string, seed, zip_number, subpath, sk, c, full_width",
+            &self.rows,
+        )
+    }
+}
+
+/// Renders a file of test vectors.
+fn render(source: &str, fields: &str, rows: &[String]) -> String {
+    let mut json = String::from("[\n");
+    write!(
+        json,
+        "    [{}],\n    [{}]",
+        JsonStr(source),
+        JsonStr(fields)
+    )
+    .expect("writing to a String cannot fail");
+    for row in rows {
+        json.push_str(",\n    ");
+        json.push_str(row);
+    }
+    json.push_str("\n]\n");
+    json
+}
+
+/// Formats a byte string as a quoted hex string.
+struct Hex<'a>(&'a [u8]);
+
+impl core::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\"")?;
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        f.write_str("\"")
+    }
+}
+
+/// Formats child indices as a comma-separated list of integers.
+struct Indices<'a>(&'a [ChildIndex]);
+
+impl core::fmt::Display for Indices<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, index) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", index.index())?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a string as a JSON string, escaping control and non-ASCII characters in the
+/// same way as the zcash-test-vectors project.
+struct JsonStr<'a>(&'a str);
+
+impl core::fmt::Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\u{8}' => f.write_str("\\b")?,
+                '\u{c}' => f.write_str("\\f")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                ' '..='~' => write!(f, "{}", c)?,
+                _ => {
+                    let mut units = [0; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        write!(f, "\\u{:04x}", unit)?;
+                    }
+                }
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests against the JSON test vectors in the `test-vectors` directory.
+    //!
+    //! Fields are looked up by name, so vectors can be added (and fields appended)
+    //! without changing this module.
+
+    use alloc::{
+        collections::BTreeMap,
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use serde_json::Value;
+
+    use super::{ArbitraryVectors, RegisteredVectors, SeedFingerprintVectors};
+    use crate::{
+        arbitrary,
+        fingerprint::SeedFingerprint,
+        registered::{self, DerivationError, PathElement},
+        with_ikm, ChildIndex,
+    };
+
+    const SEED_FINGERPRINT: &str = include_str!("../test-vectors/zip_0032_seed_fingerprint.json");
+    const ARBITRARY: &str = include_str!("../test-vectors/zip_0032_arbitrary.json");
+    const REGISTERED: &str = include_str!("../test-vectors/zip_0032_registered.json");
+
+    /// A single test vector, mapping field names to values.
+    struct TestVector(BTreeMap<String, Value>);
+
+    impl TestVector {
+        fn get(&self, field: &str) -> &Value {
+            self.0
+                .get(field)
+                .unwrap_or_else(|| panic!("test vector is missing field {}", field))
+        }
+
+        fn bytes(&self, field: &str) -> Vec<u8> {
+            decode_hex(self.get(field))
+        }
+
+        fn optional_bytes(&self, field: &str) -> Option<Vec<u8>> {
+            match self.get(field) {
+                Value::Null => None,
+                value => Some(decode_hex(value)),
+            }
+        }
+
+        fn u32(&self, field: &str) -> u32 {
+            decode_u32(self.get(field))
+        }
+
+        fn path(&self) -> Vec<ChildIndex> {
+            self.get("path")
+                .as_array()
+                .expect("path is an array")
+                .iter()
+                .map(|i| ChildIndex::from_index(decode_u32(i)).expect("hardened"))
+                .collect()
+        }
+
+        fn subpath(&self) -> Vec<(ChildIndex, Vec<u8>)> {
+            self.get("subpath")
+                .as_array()
+                .expect("subpath is an array")
+                .iter()
+                .map(|elem| {
+                    let elem = elem.as_array().expect("path element is an array");
+                    let index = ChildIndex::from_index(decode_u32(&elem[0])).expect("hardened");
+                    (index, decode_hex(&elem[1]))
+                })
+                .collect()
+        }
+    }
+
+    fn decode_hex(value: &Value) -> Vec<u8> {
+        let s = value.as_str().expect("byte strings are hex-encoded");
+        hex::decode(s).expect("valid hex")
+    }
+
+    fn decode_u32(value: &Value) -> u32 {
+        value
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .expect("integer fits in a u32")
+    }
+
+    /// Parses a file of test vectors, returning its source and vectors.
+    fn load(json: &str) -> (String, Vec<TestVector>) {
+        let parsed: Value = serde_json::from_str(json).expect("test vectors are valid JSON");
+        let rows = parsed.as_array().expect("test vectors are a JSON array");
+        assert!(rows.len() > 2, "test vector file contains no vectors");
+
+        let source = rows[0][0]
+            .as_str()
+            .expect("first element describes the source")
+            .to_string();
+        let fields = rows[1][0]
+            .as_str()
+            .expect("second element holds the field names")
+            .split(", ")
+            .collect::<Vec<_>>();
+
+        let vectors = rows[2..]
+            .iter()
+            .map(|row| {
+                let values = row.as_array().expect("each test vector is an array");
+                assert_eq!(values.len(), fields.len(), "test vector has wrong arity");
+                TestVector(
+                    fields
+                        .iter()
+                        .map(|f| f.to_string())
+                        .zip(values.iter().cloned())
+                        .collect(),
+                )
+            })
+            .collect();
+        (source, vectors)
+    }
+
+    #[test]
+    fn seed_fingerprint() {
+        for tv in load(SEED_FINGERPRINT).1 {
+            let fp = SeedFingerprint::from_seed(&tv.bytes("root_seed")).unwrap();
+            assert_eq!(&fp.to_bytes()[..], &tv.bytes("fingerprint")[..]);
+
+            let fp_str = tv.get("fingerprint_str").as_str().unwrap();
+            assert_eq!(fp.to_string(), fp_str);
+            assert_eq!(fp_str.parse::<SeedFingerprint>().unwrap(), fp);
+        }
+    }
+
+    #[test]
+    fn arbitrary() {
+        for tv in load(ARBITRARY).1 {
+            let context_string = tv.bytes("context_string");
+            let seed = tv.bytes("seed");
+
+            if let Some(tv_ikm) = tv.optional_bytes("ikm") {
+                with_ikm(&context_string, &seed, |ikm| {
+                    assert_eq!(ikm.concat(), tv_ikm)
+                });
+            }
+
+            let sk = arbitrary::SecretKey::from_path(&context_string, &seed, &tv.path());
+            assert_eq!(&sk.data()[..], &tv.bytes("sk")[..]);
+            assert_eq!(&sk.chain_code().as_bytes()[..], &tv.bytes("c")[..]);
+        }
+    }
+
+    #[test]
+    fn registered() {
+        for tv in load(REGISTERED).1 {
+            let context_string = tv.bytes("context_string");
+            let seed = tv.bytes("seed");
+            let zip_number = u16::try_from(tv.u32("zip_number")).expect("ZIP number fits in u16");
+            let subpath = tv.subpath();
+            let subpath = subpath
+                .iter()
+                .map(|(index, tag)| PathElement::new(*index, tag))
+                .collect::<Vec<_>>();
+
+            let sk =
+                registered::SecretKey::from_subpath(&context_string, &seed, zip_number, &subpath)
+                    .unwrap();
+            assert_eq!(&sk.data()[..], &tv.bytes("sk")[..]);
+            assert_eq!(&sk.chain_code().as_bytes()[..], &tv.bytes("c")[..]);
+
+            let cryptovalue =
+                registered::cryptovalue_from_subpath(&context_string, &seed, zip_number, &subpath);
+            match tv.optional_bytes("full_width") {
+                Some(full_width) => assert_eq!(&cryptovalue.unwrap()[..], &full_width[..]),
+                None => assert!(subpath.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn generator_reproduces_vectors() {
+        let (source, vectors) = load(SEED_FINGERPRINT);
+        let mut generated = SeedFingerprintVectors::new();
+        for tv in vectors {
+            generated.add(&tv.bytes("root_seed")).unwrap();
+        }
+        assert_eq!(generated.to_json(&source), SEED_FINGERPRINT);
+
+        let (source, vectors) = load(ARBITRARY);
+        let mut generated = ArbitraryVectors::new();
+        for tv in vectors {
+            generated
+                .add(&tv.bytes("context_string"), &tv.bytes("seed"), &tv.path())
+                .unwrap();
+        }
+        assert_eq!(generated.to_json(&source), ARBITRARY);
+
+        let (source, vectors) = load(REGISTERED);
+        let mut generated = RegisteredVectors::new();
+        for tv in vectors {
+            let subpath = tv.subpath();
+            let subpath = subpath
+                .iter()
+                .map(|(index, tag)| PathElement::new(*index, tag))
+                .collect::<Vec<_>>();
+            generated
+                .add(
+                    &tv.bytes("context_string"),
+                    &tv.bytes("seed"),
+                    u16::try_from(tv.u32("zip_number")).unwrap(),
+                    &subpath,
+                )
+                .unwrap();
+        }
+        assert_eq!(generated.to_json(&source), REGISTERED);
+    }
+
+    #[test]
+    fn generated_edge_cases_round_trip() {
+        let long_seed = [0xa5; 252];
+        let long_tag = [0x5a; 300];
+        let deep_path = (0..64).map(ChildIndex::hardened).collect::<Vec<_>>();
+
+        let mut vectors = ArbitraryVectors::new();
+        vectors.add(&[b'c'; 252], &long_seed, &[]).unwrap();
+        vectors.add(b"ctx", &long_seed, &deep_path).unwrap();
+        let json = vectors.to_json("Edge cases\t\"quoted\" \u{e9}\u{1f600}");
+        let (source, parsed) = load(&json);
+        assert_eq!(source, "Edge cases\t\"quoted\" \u{e9}\u{1f600}");
+        assert!(json.contains(r#"["Edge cases\t\"quoted\" \u00e9\ud83d\ude00"]"#));
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].path(), deep_path);
+
+        let subpath = [
+            PathElement::new(ChildIndex::hardened(1), &long_tag),
+            PathElement::new(ChildIndex::PRIVATE_USE, &[]),
+        ];
+        let mut vectors = RegisteredVectors::new();
+        vectors.add(b"ctx", &long_seed, 65535, &subpath).unwrap();
+        let (_, parsed) = load(&vectors.to_json("Edge cases"));
+        assert_eq!(parsed[0].u32("zip_number"), 65535);
+        assert_eq!(parsed[0].subpath()[0].1, long_tag);
+        assert_eq!(
+            parsed[0].bytes("full_width"),
+            registered::cryptovalue_from_subpath(b"ctx", &long_seed, 65535, &subpath).unwrap()
+        );
+
+        let mut vectors = SeedFingerprintVectors::new();
+        assert_eq!(vectors.add(&[0; 31]), Err(DerivationError::SeedInvalid));
+        assert_eq!(
+            ArbitraryVectors::new().add(&[], &long_seed, &[]),
+            Err(DerivationError::ContextStringInvalid)
+        );
+        assert_eq!(
+            RegisteredVectors::new().add(b"ctx", &[0; 253], 1, &subpath),
+            Err(DerivationError::SeedInvalid)
+        );
+    }
+}
@@ -0,0 +1,496 @@
+//! Gap-limit tracking of address indices.
+//!
+//! Wallets that derive addresses from a [`DiversifierIndex`] need to remember, for each
+//! account and [`Scope`], which indices have been handed out and which have been seen
+//! on chain. When restoring from a seed, the wallet must scan a window of indices past
+//! the highest index known to be used; the size of that window is the "gap limit".
+//!
+//! [`AddressIndexTracker`] keeps this state and enforces the gap limit when handing out
+//! new indices, so that a wallet restored from the same seed will always find every
+//! address that could have received funds.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt, ops::RangeInclusive};
+
+use crate::{AccountId, DiversifierIndex, Scope};
+
+/// The version byte of the serialization format written by
+/// [`AddressIndexTracker::to_bytes`].
+const SERIALIZATION_VERSION: u8 = 0;
+
+/// Errors that can occur when handing out a new address index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Handing out another index would leave more than the gap limit of consecutive
+    /// unused indices after the highest used index.
+    GapLimitReached,
+    /// The diversifier index space for this account and scope has been exhausted.
+    IndexOverflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::GapLimitReached => write!(f, "Address gap limit reached."),
+            Error::IndexOverflow => write!(f, "Diversifier index space exhausted."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Errors that can occur when parsing a serialized [`AddressIndexTracker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The serialization version is not supported.
+    UnknownVersion(u8),
+    /// The input ended before the tracker was fully read.
+    Truncated,
+    /// The input contained bytes after the end of the tracker.
+    TrailingBytes,
+    /// An account identifier was out of range.
+    InvalidAccountId,
+    /// A scope encoding was not recognized.
+    InvalidScope,
+    /// The entries were not in strictly increasing `(account, scope)` order.
+    NonCanonical,
+    /// An entry's highest used index was not below its next index.
+    InconsistentState,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownVersion(v) => write!(f, "Unknown serialization version {}.", v),
+            ParseError::Truncated => write!(f, "Serialized tracker is truncated."),
+            ParseError::TrailingBytes => write!(f, "Serialized tracker has trailing bytes."),
+            ParseError::InvalidAccountId => write!(f, "Account identifier is out of range."),
+            ParseError::InvalidScope => write!(f, "Unrecognized scope encoding."),
+            ParseError::NonCanonical => write!(f, "Tracker entries are not in canonical order."),
+            ParseError::InconsistentState => {
+                write!(f, "Highest used index is not below the next index.")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// The tracked state for a single account and scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ScopeState {
+    /// The next index that will be handed out.
+    next: u128,
+    /// The highest index that has been observed as used, if any.
+    highest_used: Option<u128>,
+}
+
+impl ScopeState {
+    const EMPTY: Self = ScopeState {
+        next: 0,
+        highest_used: None,
+    };
+
+    /// Returns the first index that lies beyond the gap-limit window.
+    fn window_end(&self, gap_limit: u32) -> u128 {
+        let start = self.highest_used.map_or(0, |i| i + 1);
+        (start + u128::from(gap_limit)).min(MAX_INDEX + 1)
+    }
+}
+
+/// The number of indices in the diversifier index space, minus one.
+const MAX_INDEX: u128 = (1 << 88) - 1;
+
+fn to_index(i: u128) -> DiversifierIndex {
+    DiversifierIndex::try_from(i).expect("tracked indices are at most 88 bits")
+}
+
+/// Tracks address index usage per account and [`Scope`], enforcing a gap limit.
+///
+/// The gap limit is the maximum number of consecutive unused indices that may be handed
+/// out after the highest index that has been observed as used (or after the start of
+/// the index space, if no index has been used yet).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressIndexTracker {
+    gap_limit: u32,
+    // Keyed by account ID and scope, so that iteration (and therefore serialization)
+    // order is deterministic.
+    state: BTreeMap<(AccountId, Scope), ScopeState>,
+}
+
+impl AddressIndexTracker {
+    /// Constructs an empty tracker with the given gap limit.
+    pub fn new(gap_limit: u32) -> Self {
+        AddressIndexTracker {
+            gap_limit,
+            state: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the gap limit enforced by this tracker.
+    pub fn gap_limit(&self) -> u32 {
+        self.gap_limit
+    }
+
+    fn get(&self, account: AccountId, scope: Scope) -> ScopeState {
+        self.state
+            .get(&(account, scope))
+            .copied()
+            .unwrap_or(ScopeState::EMPTY)
+    }
+
+    /// Hands out the next unused [`DiversifierIndex`] for the given account and scope.
+    ///
+    /// Returns an error if doing so would exceed the gap limit, or if the index space
+    /// has been exhausted. The caller is responsible for skipping indices that do not
+    /// produce valid addresses, by calling this method again.
+    pub fn next_index(
+        &mut self,
+        account: AccountId,
+        scope: Scope,
+    ) -> Result<DiversifierIndex, Error> {
+        let gap_limit = self.gap_limit;
+        let entry = self
+            .state
+            .entry((account, scope))
+            .or_insert(ScopeState::EMPTY);
+
+        if entry.next > MAX_INDEX {
+            return Err(Error::IndexOverflow);
+        }
+        if entry.next >= entry.window_end(gap_limit) {
+            return Err(Error::GapLimitReached);
+        }
+
+        let index = entry.next;
+        entry.next += 1;
+        Ok(to_index(index))
+    }
+
+    /// Records that the given index has been observed as used for the given account and
+    /// scope.
+    ///
+    /// This advances the gap-limit window, and ensures that the index will not be handed
+    /// out again by [`Self::next_index`].
+    pub fn mark_used(&mut self, account: AccountId, scope: Scope, index: DiversifierIndex) {
+        let index = u128::from(index);
+        let entry = self
+            .state
+            .entry((account, scope))
+            .or_insert(ScopeState::EMPTY);
+
+        entry.highest_used = Some(entry.highest_used.map_or(index, |h| h.max(index)));
+        entry.next = entry.next.max(index + 1);
+    }
+
+    /// Returns the highest index that has been observed as used for the given account and
+    /// scope, if any.
+    pub fn highest_used(&self, account: AccountId, scope: Scope) -> Option<DiversifierIndex> {
+        self.get(account, scope).highest_used.map(to_index)
+    }
+
+    /// Returns the range of indices that must be scanned for the given account and scope
+    /// in order to detect any further usage.
+    ///
+    /// This is the gap-limit window immediately following the highest used index (or
+    /// starting at index zero, if no index has been used). The range is truncated at the
+    /// end of the diversifier index space, and includes the last index of that space if
+    /// it falls within the window.
+    ///
+    /// Returns `None` if there is nothing to scan: either the gap limit is zero, or the
+    /// last index of the diversifier index space has been used.
+    pub fn scan_range(
+        &self,
+        account: AccountId,
+        scope: Scope,
+    ) -> Option<RangeInclusive<DiversifierIndex>> {
+        let state = self.get(account, scope);
+        let start = state.highest_used.map_or(0, |i| i + 1);
+        let end = state.window_end(self.gap_limit);
+        (start < end).then(|| to_index(start)..=to_index(end - 1))
+    }
+
+    /// Returns the accounts and scopes for which state is tracked, in ascending order.
+    pub fn tracked(&self) -> impl Iterator<Item = (AccountId, Scope)> + '_ {
+        self.state.keys().copied()
+    }
+
+    /// Serializes this tracker.
+    ///
+    /// The encoding is deterministic: two trackers that compare equal always serialize
+    /// to the same bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9 + self.state.len() * 28);
+        buf.push(SERIALIZATION_VERSION);
+        buf.extend_from_slice(&self.gap_limit.to_le_bytes());
+        buf.extend_from_slice(
+            &u32::try_from(self.state.len())
+                .expect("at most 2^32 accounts and scopes")
+                .to_le_bytes(),
+        );
+        for ((account, scope), state) in &self.state {
+            buf.extend_from_slice(&u32::from(*account).to_le_bytes());
+            buf.push(u8::try_from(scope.child_index()).expect("scope child indices are small"));
+            buf.extend_from_slice(to_index(state.next.min(MAX_INDEX)).as_bytes());
+            buf.push(u8::from(state.next > MAX_INDEX));
+            match state.highest_used {
+                Some(h) => {
+                    buf.push(1);
+                    buf.extend_from_slice(to_index(h).as_bytes());
+                }
+                None => buf.push(0),
+            }
+        }
+        buf
+    }
+
+    /// Parses a tracker previously serialized with [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let mut reader = Reader(bytes);
+
+        let version = reader.read::<1>()?[0];
+        if version != SERIALIZATION_VERSION {
+            return Err(ParseError::UnknownVersion(version));
+        }
+        let gap_limit = u32::from_le_bytes(reader.read()?);
+        let count = u32::from_le_bytes(reader.read()?);
+
+        let mut state = BTreeMap::new();
+        let mut prev = None;
+        for _ in 0..count {
+            let account = AccountId::try_from(u32::from_le_bytes(reader.read()?))
+                .map_err(|_| ParseError::InvalidAccountId)?;
+            let scope = Scope::try_from(u32::from(reader.read::<1>()?[0]))
+                .map_err(|_| ParseError::InvalidScope)?;
+            let key = (account, scope);
+            if matches!(prev, Some(p) if p >= key) {
+                return Err(ParseError::NonCanonical);
+            }
+            prev = Some(key);
+
+            let next = u128::from(DiversifierIndex::from(reader.read::<11>()?));
+            let next = match reader.read::<1>()?[0] {
+                0 => next,
+                1 if next == MAX_INDEX => MAX_INDEX + 1,
+                _ => return Err(ParseError::InconsistentState),
+            };
+            let highest_used = match reader.read::<1>()?[0] {
+                0 => None,
+                1 => Some(u128::from(DiversifierIndex::from(reader.read::<11>()?))),
+                _ => return Err(ParseError::InconsistentState),
+            };
+            if matches!(highest_used, Some(h) if h >= next) {
+                return Err(ParseError::InconsistentState);
+            }
+
+            state.insert(key, ScopeState { next, highest_used });
+        }
+
+        if !reader.0.is_empty() {
+            return Err(ParseError::TrailingBytes);
+        }
+
+        Ok(AddressIndexTracker { gap_limit, state })
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn read<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        if self.0.len() < N {
+            return Err(ParseError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(N);
+        self.0 = tail;
+        Ok(head.try_into().expect("length checked above"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::{AddressIndexTracker, Error, ParseError};
+    use crate::{AccountId, DiversifierIndex, Scope};
+
+    #[test]
+    fn gap_limit_enforced() {
+        let mut tracker = AddressIndexTracker::new(3);
+        let account = AccountId::ZERO;
+
+        for i in 0u32..3 {
+            assert_eq!(
+                tracker.next_index(account, Scope::External),
+                Ok(DiversifierIndex::from(i))
+            );
+        }
+        assert_eq!(
+            tracker.next_index(account, Scope::External),
+            Err(Error::GapLimitReached)
+        );
+
+        // Scopes are tracked independently.
+        assert_eq!(
+            tracker.next_index(account, Scope::Internal),
+            Ok(DiversifierIndex::from(0u32))
+        );
+
+        // Observing usage advances the window.
+        tracker.mark_used(account, Scope::External, DiversifierIndex::from(1u32));
+        assert_eq!(
+            tracker.highest_used(account, Scope::External),
+            Some(DiversifierIndex::from(1u32))
+        );
+        assert_eq!(
+            tracker.next_index(account, Scope::External),
+            Ok(DiversifierIndex::from(3u32))
+        );
+        assert_eq!(
+            tracker.next_index(account, Scope::External),
+            Ok(DiversifierIndex::from(4u32))
+        );
+        assert_eq!(
+            tracker.next_index(account, Scope::External),
+            Err(Error::GapLimitReached)
+        );
+    }
+
+    #[test]
+    fn mark_used_beyond_next() {
+        let mut tracker = AddressIndexTracker::new(2);
+        let account = AccountId::const_from_u32(5);
+
+        tracker.mark_used(account, Scope::Internal, DiversifierIndex::from(10u32));
+        assert_eq!(
+            tracker.next_index(account, Scope::Internal),
+            Ok(DiversifierIndex::from(11u32))
+        );
+        assert_eq!(
+            tracker.scan_range(account, Scope::Internal),
+            Some(DiversifierIndex::from(11u32)..=DiversifierIndex::from(12u32))
+        );
+        assert_eq!(
+            tracker.scan_range(account, Scope::External),
+            Some(DiversifierIndex::from(0u32)..=DiversifierIndex::from(1u32))
+        );
+        assert_eq!(
+            AddressIndexTracker::new(0).scan_range(account, Scope::External),
+            None
+        );
+    }
+
+    #[test]
+    fn scan_range_at_end_of_index_space() {
+        let mut tracker = AddressIndexTracker::new(5);
+        let max = (1u128 << 88) - 1;
+        let index = |i: u128| DiversifierIndex::try_from(i).unwrap();
+
+        tracker.mark_used(AccountId::ZERO, Scope::External, index(max - 2));
+        assert_eq!(
+            tracker.scan_range(AccountId::ZERO, Scope::External),
+            Some(index(max - 1)..=index(max))
+        );
+        assert_eq!(
+            tracker.next_index(AccountId::ZERO, Scope::External),
+            Ok(index(max - 1))
+        );
+        assert_eq!(
+            tracker.next_index(AccountId::ZERO, Scope::External),
+            Ok(index(max))
+        );
+
+        tracker.mark_used(AccountId::ZERO, Scope::External, index(max));
+        assert_eq!(tracker.scan_range(AccountId::ZERO, Scope::External), None);
+    }
+
+    #[test]
+    fn index_overflow() {
+        let mut tracker = AddressIndexTracker::new(5);
+        let max = DiversifierIndex::try_from((1u128 << 88) - 1).unwrap();
+        tracker.mark_used(AccountId::ZERO, Scope::External, max);
+        assert_eq!(
+            tracker.next_index(AccountId::ZERO, Scope::External),
+            Err(Error::IndexOverflow)
+        );
+
+        let restored = AddressIndexTracker::from_bytes(&tracker.to_bytes()).unwrap();
+        assert_eq!(restored, tracker);
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let mut tracker = AddressIndexTracker::new(20);
+        let account = AccountId::const_from_u32(1);
+        tracker.next_index(account, Scope::Internal).unwrap();
+        tracker.mark_used(
+            AccountId::ZERO,
+            Scope::External,
+            DiversifierIndex::from(7u32),
+        );
+        tracker
+            .next_index(AccountId::ZERO, Scope::External)
+            .unwrap();
+
+        let bytes = tracker.to_bytes();
+        let restored = AddressIndexTracker::from_bytes(&bytes).unwrap();
+        assert_eq!(restored, tracker);
+        assert_eq!(restored.to_bytes(), bytes);
+        assert_eq!(
+            restored.tracked().collect::<alloc::vec::Vec<_>>(),
+            [
+                (AccountId::ZERO, Scope::External),
+                (account, Scope::Internal)
+            ]
+        );
+
+        // Insertion order does not affect the encoding.
+        let mut other = AddressIndexTracker::new(20);
+        other.mark_used(
+            AccountId::ZERO,
+            Scope::External,
+            DiversifierIndex::from(7u32),
+        );
+        other.next_index(AccountId::ZERO, Scope::External).unwrap();
+        other.next_index(account, Scope::Internal).unwrap();
+        assert_eq!(other.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_matches!(
+            AddressIndexTracker::from_bytes(&[]),
+            Err(ParseError::Truncated)
+        );
+        assert_matches!(
+            AddressIndexTracker::from_bytes(&[1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ParseError::UnknownVersion(1))
+        );
+
+        let mut bytes = AddressIndexTracker::new(1).to_bytes();
+        bytes.push(0);
+        assert_matches!(
+            AddressIndexTracker::from_bytes(&bytes),
+            Err(ParseError::TrailingBytes)
+        );
+
+        let mut tracker = AddressIndexTracker::new(1);
+        tracker.mark_used(AccountId::ZERO, Scope::External, DiversifierIndex::new());
+        let mut bytes = tracker.to_bytes();
+        // Corrupt the scope byte.
+        bytes[13] = 7;
+        assert_matches!(
+            AddressIndexTracker::from_bytes(&bytes),
+            Err(ParseError::InvalidScope)
+        );
+        // Set the next index to zero, below the highest used index.
+        bytes[13] = 0;
+        bytes[14] = 0;
+        assert_matches!(
+            AddressIndexTracker::from_bytes(&bytes),
+            Err(ParseError::InconsistentState)
+        );
+    }
+}
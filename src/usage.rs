@@ -0,0 +1,247 @@
+//! Typed key usages for registered cryptovalues.
+//!
+//! A registered cryptovalue is 64 bytes of uniformly random key material. This module
+//! converts a [`LeafKey`] wrapping such a cryptovalue into commonly needed key types, so
+//! that applications do not each split or reduce the raw bytes in their own way:
+//!
+//! - [`LeafKey::symmetric_key`] splits the cryptovalue into a 32-byte symmetric key
+//!   and a 32-byte nonce seed.
+//! - [`LeafKey::ed25519_seed`] takes the first 32 bytes as an Ed25519 secret key seed.
+//! - `LeafKey::pallas_scalar` (behind the `pallas` feature flag) and
+//!   `LeafKey::jubjub_scalar` (behind the `jubjub` feature flag) reduce the full
+//!   cryptovalue, interpreted as a little-endian integer, modulo the order of the
+//!   respective scalar field. This matches the `ToScalar` reductions used elsewhere in
+//!   the Zcash protocol, and the result is statistically close to uniform.
+//!
+//! These conversions are not independent of each other: for example, the Ed25519 seed
+//! is the same as the symmetric key derived from the same cryptovalue. Each registered
+//! leaf MUST therefore be used for a single key usage. An application protocol that
+//! needs keys of several types should derive them at distinct subpaths or tags, or
+//! expand distinct sub-keys with [`LeafKey::sub_key`]. Each of these key types
+//! implements [`SubKey`] by applying the same conversion to the first 64 bytes expanded
+//! in the requested domain.
+
+use core::fmt;
+
+use zeroize::Zeroize;
+
+use crate::expand::{LeafKey, SubKey};
+
+/// A 32-byte symmetric key together with a 32-byte seed from which nonces may be
+/// derived.
+///
+/// The key material is zeroized when this value is dropped.
+#[derive(Clone)]
+pub struct SymmetricKey {
+    key: [u8; 32],
+    nonce_seed: [u8; 32],
+}
+
+impl fmt::Debug for SymmetricKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Neither the key nor the nonce seed is rendered.
+        f.debug_struct("SymmetricKey").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SymmetricKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.nonce_seed.zeroize();
+    }
+}
+
+impl SymmetricKey {
+    /// Returns the symmetric key.
+    pub fn key(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    /// Returns the nonce seed.
+    pub fn nonce_seed(&self) -> &[u8; 32] {
+        &self.nonce_seed
+    }
+}
+
+/// A 32-byte Ed25519 secret key seed, as defined in [RFC 8032 § 5.1.5].
+///
+/// The seed is zeroized when this value is dropped.
+///
+/// [RFC 8032 § 5.1.5]: https://www.rfc-editor.org/rfc/rfc8032#section-5.1.5
+#[derive(Clone)]
+pub struct Ed25519Seed([u8; 32]);
+
+impl fmt::Debug for Ed25519Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The seed is not rendered.
+        f.debug_struct("Ed25519Seed").finish_non_exhaustive()
+    }
+}
+
+impl Drop for Ed25519Seed {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Ed25519Seed {
+    /// Returns the bytes of this seed.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl SubKey for SymmetricKey {
+    fn expand_from(leaf: &LeafKey, domain: u8) -> Self {
+        expand_leaf(leaf, domain).symmetric_key()
+    }
+}
+
+impl SubKey for Ed25519Seed {
+    fn expand_from(leaf: &LeafKey, domain: u8) -> Self {
+        expand_leaf(leaf, domain).ed25519_seed()
+    }
+}
+
+#[cfg(feature = "pallas")]
+impl SubKey for pasta_curves::pallas::Scalar {
+    fn expand_from(leaf: &LeafKey, domain: u8) -> Self {
+        expand_leaf(leaf, domain).pallas_scalar()
+    }
+}
+
+#[cfg(feature = "jubjub")]
+impl SubKey for jubjub::Fr {
+    fn expand_from(leaf: &LeafKey, domain: u8) -> Self {
+        expand_leaf(leaf, domain).jubjub_scalar()
+    }
+}
+
+/// Expands 64 bytes from `leaf` in the given domain, as a [`LeafKey`] to be converted.
+fn expand_leaf(leaf: &LeafKey, domain: u8) -> LeafKey {
+    let mut bytes = [0; 64];
+    leaf.expand(domain, &mut bytes);
+    let expanded = LeafKey::from_cryptovalue(bytes);
+    bytes.zeroize();
+    expanded
+}
+
+impl LeafKey {
+    /// Splits this cryptovalue into a symmetric key (the first 32 bytes) and a nonce
+    /// seed (the last 32 bytes).
+    pub fn symmetric_key(&self) -> SymmetricKey {
+        let (key, nonce_seed) = self.cryptovalue().split_at(32);
+        SymmetricKey {
+            key: key.try_into().expect("32 bytes"),
+            nonce_seed: nonce_seed.try_into().expect("32 bytes"),
+        }
+    }
+
+    /// Returns the first 32 bytes of this cryptovalue as an Ed25519 seed.
+    pub fn ed25519_seed(&self) -> Ed25519Seed {
+        Ed25519Seed(self.cryptovalue()[..32].try_into().expect("32 bytes"))
+    }
+
+    /// Reduces this cryptovalue, as a little-endian integer, to a Pallas scalar.
+    #[cfg(feature = "pallas")]
+    pub fn pallas_scalar(&self) -> pasta_curves::pallas::Scalar {
+        use pasta_curves::group::ff::FromUniformBytes;
+
+        pasta_curves::pallas::Scalar::from_uniform_bytes(self.cryptovalue())
+    }
+
+    /// Reduces this cryptovalue, as a little-endian integer, to a Jubjub scalar.
+    #[cfg(feature = "jubjub")]
+    pub fn jubjub_scalar(&self) -> jubjub::Fr {
+        jubjub::Fr::from_bytes_wide(self.cryptovalue())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ed25519Seed;
+    use crate::expand::LeafKey;
+
+    // The full-width cryptovalue from the last registered key derivation test vector. The
+    // expected scalars below are generated by
+    // test-vectors/generators/registered_expand.py.
+    const CRYPTOVALUE: [u8; 64] = [
+        0x7f, 0x85, 0x3e, 0xef, 0x00, 0x1b, 0x1b, 0xc5, 0xa1, 0xa5, 0xe6, 0x7f, 0x5d, 0xfd, 0x0e,
+        0x90, 0x42, 0x75, 0x96, 0xd4, 0x84, 0x2f, 0x5b, 0x10, 0xa1, 0x11, 0xe9, 0x7c, 0x40, 0x73,
+        0x20, 0x3c, 0xed, 0xf6, 0xb8, 0x0a, 0x85, 0x14, 0x5e, 0x50, 0x61, 0xac, 0xd2, 0x9b, 0xc5,
+        0xa4, 0xe3, 0x49, 0xb1, 0x4f, 0x85, 0x57, 0xa7, 0x03, 0x3e, 0x23, 0xb0, 0x66, 0xb7, 0xce,
+        0x24, 0x09, 0xd9, 0x73,
+    ];
+
+    #[test]
+    fn symmetric_key() {
+        let key = LeafKey::from_cryptovalue(CRYPTOVALUE).symmetric_key();
+        assert_eq!(&key.key()[..], &CRYPTOVALUE[..32]);
+        assert_eq!(&key.nonce_seed()[..], &CRYPTOVALUE[32..]);
+    }
+
+    #[test]
+    fn ed25519_seed() {
+        let seed = LeafKey::from_cryptovalue(CRYPTOVALUE).ed25519_seed();
+        assert_eq!(&seed.as_bytes()[..], &CRYPTOVALUE[..32]);
+
+        let leaf = LeafKey::from_cryptovalue(CRYPTOVALUE);
+        let mut expanded = [0; 64];
+        leaf.expand(5, &mut expanded);
+        assert_eq!(
+            &leaf.sub_key::<Ed25519Seed>(5).as_bytes()[..],
+            &expanded[..32]
+        );
+    }
+
+    #[cfg(feature = "pallas")]
+    #[test]
+    fn pallas_scalar() {
+        use pasta_curves::group::ff::PrimeField;
+
+        let scalar = LeafKey::from_cryptovalue(CRYPTOVALUE).pallas_scalar();
+        assert_eq!(
+            scalar.to_repr(),
+            [
+                0x14, 0x75, 0x2d, 0x32, 0x50, 0xab, 0xf0, 0x64, 0x6e, 0xad, 0x00, 0x20, 0x7e, 0x8d,
+                0x5e, 0x39, 0x5d, 0xa7, 0x7f, 0x14, 0x2b, 0x4c, 0x28, 0xcb, 0x0f, 0xf6, 0x74, 0x0e,
+                0x35, 0x1a, 0x3b, 0x22,
+            ]
+        );
+
+        let sub_key =
+            LeafKey::from_cryptovalue(CRYPTOVALUE).sub_key::<pasta_curves::pallas::Scalar>(3);
+        assert_eq!(
+            sub_key.to_repr(),
+            [
+                0x65, 0xaa, 0xbf, 0x97, 0xd4, 0x76, 0x63, 0xc4, 0x20, 0xe9, 0xa8, 0xdd, 0x8e, 0x09,
+                0x7f, 0x25, 0x4a, 0x17, 0xcd, 0x65, 0xa8, 0xce, 0x26, 0x79, 0x4b, 0x24, 0xe0, 0x29,
+                0x2d, 0xe5, 0x54, 0x13,
+            ]
+        );
+    }
+
+    #[cfg(feature = "jubjub")]
+    #[test]
+    fn jubjub_scalar() {
+        let scalar = LeafKey::from_cryptovalue(CRYPTOVALUE).jubjub_scalar();
+        assert_eq!(
+            scalar.to_bytes(),
+            [
+                0x9d, 0xb0, 0x3d, 0x44, 0xb5, 0xa1, 0xef, 0xe1, 0x13, 0x35, 0x1f, 0x21, 0xf8, 0xc4,
+                0x40, 0xa6, 0x4b, 0xd6, 0xca, 0x1d, 0xe0, 0x57, 0xd1, 0xb3, 0x58, 0xcd, 0x64, 0x2f,
+                0x03, 0xef, 0x9a, 0x01,
+            ]
+        );
+
+        let sub_key = LeafKey::from_cryptovalue(CRYPTOVALUE).sub_key::<jubjub::Fr>(4);
+        assert_eq!(
+            sub_key.to_bytes(),
+            [
+                0x83, 0xfd, 0xb3, 0x26, 0xd2, 0xa7, 0xe8, 0xfc, 0x35, 0x7f, 0xb0, 0x5b, 0xcd, 0xd3,
+                0xf1, 0x2e, 0x1e, 0x9f, 0x40, 0x7b, 0x51, 0x02, 0x63, 0x21, 0xa6, 0x08, 0x6b, 0xdd,
+                0xbb, 0x43, 0x5c, 0x06,
+            ]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+#!/usr/bin/env python3
+"""Expansion and typed key usage vectors for `src/expand.rs` and `src/usage.rs`.
+
+The leaf is the full-width cryptovalue of the last registered key derivation test
+vector. Expansion treats it as a registered extended key (sk, c), and block `counter`
+in domain `domain` is its full-width child cryptovalue at `counter'` with tag
+`[domain]`. Scalars are the cryptovalue, or the first 64 expanded bytes, reduced as a
+little-endian integer modulo the order of the scalar field.
+"""
+
+from zip32 import (
+    derive_child,
+    derive_child_cryptovalue,
+    hardened,
+    registered_master,
+    rust_bytes,
+)
+
+PALLAS_Q = 0x40000000000000000000000000000000224698FC0994A8DD8C46EB2100000001
+JUBJUB_R = 0x0E7DB4EA6533AFA906673B0101343B00A6682093CCC81082D0970E5ED6F72CB7
+
+
+def expand(leaf, domain, length):
+    key = (leaf[:32], leaf[32:])
+    out = b''
+    counter = 0
+    while len(out) < length:
+        out += derive_child_cryptovalue(key, hardened(counter), bytes([domain]))
+        counter += 1
+    return out[:length]
+
+
+def reduce(data, modulus):
+    return (int.from_bytes(data, 'little') % modulus).to_bytes(32, 'little')
+
+
+def main():
+    context_string = b'Zcash test vectors'
+    seed = bytes(range(32))
+    tag = b'trans rights are human rights'
+
+    zip_root = derive_child(registered_master(context_string, seed), hardened(1))
+    parent = derive_child(zip_root, hardened(2), tag)
+    leaf = derive_child_cryptovalue(parent, hardened(3))
+    print('cryptovalue: [%s]' % rust_bytes(leaf))
+
+    print('expand(1, 96): [%s]' % rust_bytes(expand(leaf, 1, 96)))
+    sub_key = expand(leaf, 2, 64)
+    print('sub_key::<SymmetricKey>(2) key: [%s]' % rust_bytes(sub_key[:32]))
+    print('sub_key::<SymmetricKey>(2) nonce_seed: [%s]' % rust_bytes(sub_key[32:]))
+
+    print('pallas_scalar: [%s]' % rust_bytes(reduce(leaf, PALLAS_Q)))
+    print('jubjub_scalar: [%s]' % rust_bytes(reduce(leaf, JUBJUB_R)))
+    print('sub_key::<pallas::Scalar>(3): [%s]' % rust_bytes(reduce(expand(leaf, 3, 64), PALLAS_Q)))
+    print('sub_key::<jubjub::Fr>(4): [%s]' % rust_bytes(reduce(expand(leaf, 4, 64), JUBJUB_R)))
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,32 @@
+#!/usr/bin/env python3
+"""Registered private-use subtree vectors for `src/registered.rs`.
+
+Derives the subtree root at `m_{context} / (2^31 - 1)'`, its child at `1'` with tag
+"tag", and the cryptovalue at the same position.
+"""
+
+from zip32 import (
+    derive_child,
+    derive_child_cryptovalue,
+    hardened,
+    registered_master,
+    rust_bytes,
+)
+
+
+def main():
+    context_string = b'Zcash test vectors'
+    seed = bytes(range(32))
+
+    root = derive_child(registered_master(context_string, seed), hardened(0x7fffffff))
+    print('root sk: [%s]' % rust_bytes(root[0]))
+    print('root c: [%s]' % rust_bytes(root[1]))
+
+    child = derive_child(root, hardened(1), b'tag')
+    print('child sk: [%s]' % rust_bytes(child[0]))
+    print('child c: [%s]' % rust_bytes(child[1]))
+    print('cryptovalue: [%s]' % rust_bytes(derive_child_cryptovalue(root, hardened(1), b'tag')))
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,99 @@
+#!/usr/bin/env python3
+"""Sapling diversifier test vectors for `src/sapling.rs`.
+
+Implements FF1-AES256 (NIST SP 800-38G) directly on top of AES, checks it against
+the NIST radix-10 AES-256 samples, and then derives diversifiers as specified in
+ZIP 32: d_j = FF1-AES256.Encrypt(dk, "", I2LEBSP_88(j)).
+
+Requires the `cryptography` package for AES.
+"""
+
+import math
+
+from cryptography.hazmat.primitives.ciphers import Cipher, algorithms, modes
+
+
+def aes(key, block):
+    encryptor = Cipher(algorithms.AES(key), modes.ECB()).encryptor()
+    return encryptor.update(block) + encryptor.finalize()
+
+
+def num(x, radix):
+    n = 0
+    for digit in x:
+        n = n * radix + digit
+    return n
+
+
+def str_m(n, radix, m):
+    out = [0] * m
+    for i in range(m - 1, -1, -1):
+        out[i] = n % radix
+        n //= radix
+    return out
+
+
+def prf(key, data):
+    y = bytes(16)
+    for i in range(0, len(data), 16):
+        y = aes(key, bytes(a ^ b for a, b in zip(y, data[i:i + 16])))
+    return y
+
+
+def ff1_encrypt(key, tweak, x, radix):
+    n = len(x)
+    u = n // 2
+    v = n - u
+    a, b = x[:u], x[u:]
+    byte_len = math.ceil(math.ceil(v * math.log2(radix)) / 8)
+    d = 4 * math.ceil(byte_len / 4) + 4
+    t = len(tweak)
+    p = (bytes([1, 2, 1]) + radix.to_bytes(3, 'big') + bytes([10, u % 256])
+         + n.to_bytes(4, 'big') + t.to_bytes(4, 'big'))
+    for i in range(10):
+        q = (tweak + bytes((-t - byte_len - 1) % 16) + bytes([i])
+             + num(b, radix).to_bytes(byte_len, 'big'))
+        r = prf(key, p + q)
+        s = r
+        j = 1
+        while len(s) < d:
+            s += aes(key, bytes(x ^ y for x, y in zip(r, j.to_bytes(16, 'big'))))
+            j += 1
+        y = int.from_bytes(s[:d], 'big')
+        m = u if i % 2 == 0 else v
+        c = (num(a, radix) + y) % (radix ** m)
+        a, b = b, str_m(c, radix, m)
+    return a + b
+
+
+def i2lebsp(data):
+    return [(byte >> i) & 1 for byte in data for i in range(8)]
+
+
+def lebs2osp(bits):
+    return bytes(sum(bits[8 * k + i] << i for i in range(8)) for k in range(len(bits) // 8))
+
+
+def check_nist_samples():
+    key = bytes.fromhex('2B7E151628AED2A6ABF7158809CF4F3CEF4359D8D580AA4F7F036D6F04FC6A94')
+    pt = list(range(10))
+    assert ff1_encrypt(key, b'', pt, 10) == [6, 6, 5, 7, 6, 6, 7, 0, 0, 9]
+    tweak = bytes.fromhex('39383736353433323130')
+    assert ff1_encrypt(key, tweak, pt, 10) == [1, 0, 0, 1, 6, 2, 3, 4, 6, 3]
+
+
+def rust_bytes(data):
+    return ', '.join('0x%02x' % b for b in data)
+
+
+def main():
+    check_nist_samples()
+    dk = bytes(range(32))
+    print('const DK: [u8; 32] = [%s];' % rust_bytes(dk))
+    for j in [0, 1, (1 << 88) - 1]:
+        d = lebs2osp(ff1_encrypt(dk, b'', i2lebsp(j.to_bytes(11, 'little')), 2))
+        print('TestVector { j: 0x%x, d: [%s] },' % (j, rust_bytes(d)))
+
+
+if __name__ == '__main__':
+    main()
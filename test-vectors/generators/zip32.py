@@ -0,0 +1,53 @@
+"""Hardened-only key derivation as specified in ZIP 32, shared by the generators."""
+
+import hashlib
+
+REGISTERED_MKG_DOMAIN = b'ZIPRegistered_KD'
+REGISTERED_CKD_DOMAIN = 0xAC
+
+
+def blake2b_512(personalization, data):
+    return hashlib.blake2b(data, digest_size=64, person=personalization).digest()
+
+
+def prf_expand(sk, t):
+    return blake2b_512(b'Zcash_ExpandSeed', sk + t)
+
+
+def hardened(i):
+    return i | (1 << 31)
+
+
+def registered_master(context_string, seed):
+    """Returns the registered master key (sk, c) for a context string and seed."""
+    i = blake2b_512(
+        REGISTERED_MKG_DOMAIN,
+        bytes([len(context_string)]) + context_string + bytes([len(seed)]) + seed,
+    )
+    return i[:32], i[32:]
+
+
+def ckdh(key, index, lead=0, tag=b''):
+    """Returns I for the child of `key` at `index`, as in ZIP 32's CKDh.
+
+    The lead byte and tag are omitted from the PRF^expand input when the lead is zero
+    and the tag is empty.
+    """
+    sk, c = key
+    t = bytes([REGISTERED_CKD_DOMAIN]) + sk + index.to_bytes(4, 'little')
+    if lead != 0 or tag:
+        t += bytes([lead]) + tag
+    return prf_expand(c, t)
+
+
+def derive_child(key, index, tag=b''):
+    i = ckdh(key, index, 0, tag)
+    return i[:32], i[32:]
+
+
+def derive_child_cryptovalue(key, index, tag=b''):
+    return ckdh(key, index, 1, tag)
+
+
+def rust_bytes(data):
+    return ', '.join('0x%02x' % b for b in data)
@@ -0,0 +1,84 @@
+#!/usr/bin/env python3
+"""Seed fingerprint vectors, written to `test-vectors/zip_0032_seed_fingerprint.json`.
+
+Computes seed fingerprints as specified in ZIP 32, and their Bech32m encodings
+(BIP 350) with the human-readable part "zip32seedfp".
+"""
+
+import hashlib
+import json
+import os
+
+CHARSET = 'qpzry9x8gf2tvdw0s3jn54khce6mua7l'
+BECH32M_CONST = 0x2bc830a3
+
+
+def polymod(values):
+    generator = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3]
+    chk = 1
+    for v in values:
+        top = chk >> 25
+        chk = (chk & 0x1ffffff) << 5 ^ v
+        for i in range(5):
+            chk ^= generator[i] if (top >> i) & 1 else 0
+    return chk
+
+
+def bech32m_encode(hrp, data):
+    acc = 0
+    bits = 0
+    five = []
+    for b in data:
+        acc = (acc << 8) | b
+        bits += 8
+        while bits >= 5:
+            bits -= 5
+            five.append((acc >> bits) & 31)
+    if bits:
+        five.append((acc << (5 - bits)) & 31)
+    expanded = [ord(c) >> 5 for c in hrp] + [0] + [ord(c) & 31 for c in hrp]
+    pm = polymod(expanded + five + [0] * 6) ^ BECH32M_CONST
+    checksum = [(pm >> 5 * (5 - i)) & 31 for i in range(6)]
+    return hrp + '1' + ''.join(CHARSET[d] for d in five + checksum)
+
+
+def seed_fingerprint(seed):
+    assert 32 <= len(seed) <= 252
+    return hashlib.blake2b(
+        bytes([len(seed)]) + seed, digest_size=32, person=b'Zcash_HD_Seed_FP'
+    ).digest()
+
+
+SEEDS = [
+    bytes(range(32)),
+    bytes(32),
+    bytes([0xff] * 64),
+    bytes(i % 256 for i in range(252)),
+    hashlib.blake2b(b'zip32 seed fingerprint test vector', digest_size=48).digest(),
+]
+
+
+def main():
+    rows = []
+    for seed in SEEDS:
+        fp = seed_fingerprint(seed)
+        rows.append([seed.hex(), fp.hex(), bech32m_encode('zip32seedfp', fp)])
+
+    # The seed fingerprint vector from the test in `src/fingerprint.rs`.
+    assert rows[0][1] == 'deff604c246710f7176dead02aa746f2fd8d5389f7072556dcb555fdbe5e3ae3'
+    assert rows[0][2] == 'zip32seedfp1mmlkqnpyvug0w9mdatgz4f6x7t7c65uf7urj24kuk42lm0j78t3sne2h0z'
+
+    lines = [
+        '[',
+        '    ["Generated by test-vectors/generators/zip_0032_seed_fingerprint.py"],',
+        '    [%s],' % json.dumps('root_seed, fingerprint, fingerprint_str'),
+        ',\n'.join('    ' + json.dumps(row) for row in rows),
+        ']',
+    ]
+    out = os.path.join(os.path.dirname(__file__), '..', 'zip_0032_seed_fingerprint.json')
+    with open(out, 'w') as f:
+        f.write('\n'.join(lines) + '\n')
+
+
+if __name__ == '__main__':
+    main()
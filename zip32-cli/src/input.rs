@@ -0,0 +1,36 @@
+//! Reading of secret inputs.
+
+use std::{
+    fs::File,
+    io::{self, Read},
+};
+
+use zeroize::Zeroizing;
+
+use crate::Error;
+
+/// Reads a hex-encoded seed from the file at `path`, or from standard input if no path is
+/// given.
+///
+/// Surrounding whitespace is ignored. The seed is never included in error messages.
+pub(crate) fn read_seed(path: Option<&str>) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let mut encoded = Zeroizing::new(String::new());
+    match path {
+        Some(path) => File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut encoded))
+            .map_err(|e| Error::Input(format!("could not read seed file {}: {}", path, e)))?,
+        None => io::stdin()
+            .read_to_string(&mut encoded)
+            .map_err(|e| Error::Input(format!("could not read seed from stdin: {}", e)))?,
+    };
+
+    let seed = hex::decode(encoded.trim())
+        .map(Zeroizing::new)
+        .map_err(|_| Error::Input("seed is not a valid hex string".to_string()))?;
+    if seed.len() < 32 || seed.len() > 252 {
+        return Err(Error::Input(
+            "seed must be between 32 and 252 bytes, inclusive".to_string(),
+        ));
+    }
+    Ok(seed)
+}
@@ -0,0 +1,274 @@
+//! A command-line tool for ZIP 32 key derivation and seed fingerprinting.
+//!
+//! Seeds are read as hex strings from standard input, or from a file given with
+//! `--seed-file`, and are never written to the output or included in error messages.
+
+use std::{env, fmt, process};
+
+use zip32::{arbitrary, fingerprint::SeedFingerprint, path::Path, registered, DiversifierIndex};
+
+mod input;
+
+use input::read_seed;
+
+const USAGE: &str = "\
+Usage:
+  zip32-cli fingerprint [--seed-file <FILE>]
+  zip32-cli derive arbitrary --context <STRING> [--seed-file <FILE>] <PATH>
+  zip32-cli derive registered --context <STRING> --zip <NUMBER> [--key]
+                              [--seed-file <FILE>] <SUBPATH>
+  zip32-cli path <PATH>
+  zip32-cli diversifier-index (<INDEX> | --bytes <HEX>)
+
+Seeds are read as hex from standard input, unless --seed-file is given.
+
+Paths are written as `m/32'/133'/0'`. Registered subpaths are relative to the ZIP
+subtree root, and each element may carry a hex-encoded tag, as in `2':7461/3'`.
+
+By default, `derive registered` prints the 64-byte cryptovalue at the subpath; with
+--key, it prints the key and chain code instead.
+
+A diversifier <INDEX> is a decimal integer. Diversifier index bytes are the 11
+little-endian bytes of the index, in hex.
+";
+
+/// An error that causes the tool to exit unsuccessfully.
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// The command line was invalid.
+    Usage(String),
+    /// An input was invalid, or derivation failed.
+    Input(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+            Error::Input(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => print!("{}", output),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(match e {
+                Error::Usage(_) => 2,
+                Error::Input(_) => 1,
+            });
+        }
+    }
+}
+
+/// Runs the command given by `args`, returning its output.
+fn run(args: &[String]) -> Result<String, Error> {
+    let (command, rest) = args
+        .split_first()
+        .ok_or_else(|| Error::Usage("no command given".to_string()))?;
+    match command.as_str() {
+        "fingerprint" => fingerprint(Options::parse(rest, &["--seed-file"], &[])?),
+        "derive" => {
+            let (kind, rest) = rest
+                .split_first()
+                .ok_or_else(|| Error::Usage("no derivation kind given".to_string()))?;
+            match kind.as_str() {
+                "arbitrary" => {
+                    derive_arbitrary(Options::parse(rest, &["--context", "--seed-file"], &[])?)
+                }
+                "registered" => derive_registered(Options::parse(
+                    rest,
+                    &["--context", "--zip", "--seed-file"],
+                    &["--key"],
+                )?),
+                other => Err(Error::Usage(format!("unknown derivation kind: {}", other))),
+            }
+        }
+        "path" => parse_path(Options::parse(rest, &[], &[])?),
+        "diversifier-index" => diversifier_index(Options::parse(rest, &["--bytes"], &[])?),
+        "help" | "--help" | "-h" => Ok(USAGE.to_string()),
+        other => Err(Error::Usage(format!("unknown command: {}", other))),
+    }
+}
+
+/// The options and positional arguments of a command.
+struct Options<'a> {
+    values: Vec<(&'a str, &'a str)>,
+    flags: Vec<&'a str>,
+    positional: Vec<&'a str>,
+}
+
+impl<'a> Options<'a> {
+    /// Parses `args`, accepting the given options (which take a value) and flags.
+    fn parse(args: &'a [String], options: &[&'a str], flags: &[&'a str]) -> Result<Self, Error> {
+        let mut parsed = Options {
+            values: vec![],
+            flags: vec![],
+            positional: vec![],
+        };
+        let mut args = args.iter().map(String::as_str);
+        while let Some(arg) = args.next() {
+            if let Some(name) = options.iter().find(|name| **name == arg) {
+                if parsed.values.iter().any(|(n, _)| n == name) {
+                    return Err(Error::Usage(format!("{} given more than once", name)));
+                }
+                let value = args
+                    .next()
+                    .ok_or_else(|| Error::Usage(format!("{} requires a value", name)))?;
+                parsed.values.push((*name, value));
+            } else if let Some(name) = flags.iter().find(|name| **name == arg) {
+                parsed.flags.push(*name);
+            } else if arg.starts_with("--") {
+                return Err(Error::Usage(format!("unknown option: {}", arg)));
+            } else {
+                parsed.positional.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn value(&self, name: &str) -> Option<&'a str> {
+        self.values
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| *v)
+    }
+
+    fn required(&self, name: &str) -> Result<&'a str, Error> {
+        self.value(name)
+            .ok_or_else(|| Error::Usage(format!("{} is required", name)))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    /// Returns the positional arguments, checking that there are at most `max` of them.
+    fn positional(&self, max: usize) -> Result<&[&'a str], Error> {
+        if self.positional.len() > max {
+            Err(Error::Usage(format!(
+                "unexpected argument: {}",
+                self.positional[max]
+            )))
+        } else {
+            Ok(&self.positional)
+        }
+    }
+}
+
+/// Parses and validates a context string.
+fn context_string<'a>(options: &Options<'a>) -> Result<&'a [u8], Error> {
+    let context = options.required("--context")?.as_bytes();
+    if context.is_empty() || context.len() > 252 {
+        return Err(Error::Input(
+            "context string must be between 1 and 252 bytes, inclusive".to_string(),
+        ));
+    }
+    Ok(context)
+}
+
+/// Parses the single (possibly omitted) path argument of a command.
+fn path_argument(options: &Options<'_>) -> Result<Path, Error> {
+    let path = options.positional(1)?.first().copied().unwrap_or("");
+    path.parse()
+        .map_err(|e| Error::Input(format!("invalid path: {}", e)))
+}
+
+fn fingerprint(options: Options<'_>) -> Result<String, Error> {
+    options.positional(0)?;
+    let seed = read_seed(options.value("--seed-file"))?;
+    let fp = SeedFingerprint::from_seed(&seed).expect("seed length checked");
+    Ok(format!(
+        "bech32m: {}\nhex: {}\n",
+        fp,
+        hex::encode(&fp.to_bytes())
+    ))
+}
+
+fn derive_arbitrary(options: Options<'_>) -> Result<String, Error> {
+    let context = context_string(&options)?;
+    let path = path_argument(&options)?;
+    if path.has_tags() {
+        return Err(Error::Input(
+            "arbitrary key derivation does not support tags".to_string(),
+        ));
+    }
+    let seed = read_seed(options.value("--seed-file"))?;
+
+    let key = arbitrary::SecretKey::from_path(context, &seed, &path.child_indices());
+    Ok(format!(
+        "sk: {}\nchain_code: {}\n",
+        hex::encode(key.data()),
+        hex::encode(key.chain_code().as_bytes())
+    ))
+}
+
+fn derive_registered(options: Options<'_>) -> Result<String, Error> {
+    let context = context_string(&options)?;
+    let zip_number = options
+        .required("--zip")?
+        .parse::<u16>()
+        .map_err(|_| Error::Input("ZIP number must be an integer less than 2^16".to_string()))?;
+    let path = path_argument(&options)?;
+    let seed = read_seed(options.value("--seed-file"))?;
+
+    let subpath = path.subpath();
+    let root = registered::ZipRoot::new(context, &seed, zip_number)
+        .map_err(|e| Error::Input(e.to_string()))?;
+    if options.flag("--key") {
+        let key = root.secret_key(&subpath);
+        Ok(format!(
+            "sk: {}\nchain_code: {}\n",
+            hex::encode(key.data()),
+            hex::encode(key.chain_code().as_bytes())
+        ))
+    } else {
+        let cryptovalue = root
+            .cryptovalue(&subpath)
+            .map_err(|e| Error::Input(e.to_string()))?;
+        Ok(format!("cryptovalue: {}\n", hex::encode(&cryptovalue)))
+    }
+}
+
+fn parse_path(options: Options<'_>) -> Result<String, Error> {
+    if options.positional(1)?.is_empty() {
+        return Err(Error::Usage("no path given".to_string()));
+    }
+    let path = path_argument(&options)?;
+    let indices = path
+        .child_indices()
+        .iter()
+        .map(|index| index.index().to_string())
+        .collect::<Vec<_>>();
+    Ok(format!(
+        "path: {}\ndepth: {}\nindices: {}\n",
+        path,
+        path.elements().len(),
+        indices.join(" ")
+    ))
+}
+
+fn diversifier_index(options: Options<'_>) -> Result<String, Error> {
+    let positional = options.positional(1)?;
+    let index = match (positional.first(), options.value("--bytes")) {
+        (Some(index), None) => index
+            .parse::<DiversifierIndex>()
+            .map_err(|e| Error::Input(e.to_string()))?,
+        (None, Some(bytes)) => DiversifierIndex::from_le_hex(bytes).map_err(|_| {
+            Error::Input("diversifier index bytes must be 11 bytes of hex".to_string())
+        })?,
+        _ => {
+            return Err(Error::Usage(
+                "exactly one of <INDEX> or --bytes is required".to_string(),
+            ))
+        }
+    };
+    Ok(format!(
+        "decimal: {}\nbytes: {}\n",
+        index,
+        index.to_le_hex()
+    ))
+}
@@ -0,0 +1,261 @@
+//! Integration tests for `zip32-cli`, using the test vectors from the `zip32` crate.
+
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+const SEED: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const CONTEXT: &str = "Zcash test vectors";
+// "trans rights are human rights"
+const TAG: &str = "7472616e7320726967687473206172652068756d616e20726967687473";
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_zip32-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The tool may exit without reading its input, so write errors are ignored.
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn run_ok(args: &[&str], stdin: &str) -> String {
+    let output = run(args, stdin);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn run_err(args: &[&str], stdin: &str, code: i32) -> String {
+    let output = run(args, stdin);
+    assert_eq!(output.status.code(), Some(code));
+    assert!(output.stdout.is_empty());
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn fingerprint() {
+    let expected = "\
+bech32m: zip32seedfp1mmlkqnpyvug0w9mdatgz4f6x7t7c65uf7urj24kuk42lm0j78t3sne2h0z
+hex: deff604c246710f7176dead02aa746f2fd8d5389f7072556dcb555fdbe5e3ae3
+";
+    assert_eq!(run_ok(&["fingerprint"], SEED), expected);
+    assert_eq!(run_ok(&["fingerprint"], &format!("  {}\n", SEED)), expected);
+
+    let dir = std::env::temp_dir().join(format!("zip32-cli-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let seed_file = dir.join("seed");
+    std::fs::write(&seed_file, SEED).unwrap();
+    assert_eq!(
+        run_ok(
+            &["fingerprint", "--seed-file", seed_file.to_str().unwrap()],
+            ""
+        ),
+        expected
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn derive_arbitrary() {
+    let vectors = [
+        (
+            "m",
+            "e9da8806409dc3c3ebd1fc2a71c879c13dd7aa93ede803bf1a83414b9d3b158a",
+            "65a748f2905f7a8aab9f3d02f1b26c3d65c82994ce59a086d4c651d8a81cec51",
+        ),
+        (
+            "m/1'/2'/3'",
+            "fc4b6e93b0e42f7a762ca0c6522ccd1045cab506b372452af7306c87389ab62c",
+            "e89bf2ed73f5e0887542e36793fac82c508ab5d99198578227b241fbac198429",
+        ),
+        (
+            "m/32'/133'/0'",
+            "bf60078362a09234fcbc6bf6c8a87bde9fc73776bf93f37adbcc439a85574a9a",
+            "2b657e08f67a570c53b9ed30611e6a2f822662b4887a8cfb469e9d0d9817011a",
+        ),
+    ];
+    for (path, sk, c) in vectors {
+        assert_eq!(
+            run_ok(&["derive", "arbitrary", "--context", CONTEXT, path], SEED),
+            format!("sk: {}\nchain_code: {}\n", sk, c)
+        );
+    }
+
+    assert!(run_err(
+        &["derive", "arbitrary", "--context", CONTEXT, "m/1':00"],
+        SEED,
+        1
+    )
+    .contains("does not support tags"));
+}
+
+#[test]
+fn derive_registered() {
+    let tagged = format!("2':{}", TAG);
+    assert_eq!(
+        run_ok(
+            &[
+                "derive",
+                "registered",
+                "--context",
+                CONTEXT,
+                "--zip",
+                "1",
+                "--key",
+                ""
+            ],
+            SEED
+        ),
+        "sk: 53a71507e6dfda588bc1e138c2657c9269e55f5d9b99e3887c134008193a2f47\n\
+         chain_code: 08bb26aae21d4efdc3249b9557fcd9131e8b9827241d9f61d0d774bb4fed3de6\n"
+    );
+    assert_eq!(
+        run_ok(
+            &[
+                "derive",
+                "registered",
+                "--context",
+                CONTEXT,
+                "--zip",
+                "1",
+                "--key",
+                &tagged
+            ],
+            SEED
+        ),
+        "sk: 02dc25cc40310eed08b028e07fae9adbee2fbe56a4694def0401e656dfae0211\n\
+         chain_code: d8f9d8a1f81d1b5d5506b5ff942d2ff3dae7a63f57d6b8c7fbe58149823cc6ec\n"
+    );
+    assert_eq!(
+        run_ok(
+            &[
+                "derive",
+                "registered",
+                "--context",
+                CONTEXT,
+                "--zip",
+                "1",
+                &tagged
+            ],
+            SEED
+        ),
+        "cryptovalue: 255d75b5f97dd880a14460ab0a28938e7ba497ceb1457fff2992e9015a8403f8\
+         c08112b7a94cf539c21c9da7ee99897be9476b6813532ee22c8947d753b72bdf\n"
+    );
+    assert_eq!(
+        run_ok(
+            &[
+                "derive",
+                "registered",
+                "--context",
+                CONTEXT,
+                "--zip",
+                "1",
+                &format!("{}/3'", tagged)
+            ],
+            SEED
+        ),
+        "cryptovalue: 7f853eef001b1bc5a1a5e67f5dfd0e90427596d4842f5b10a111e97c4073203c\
+         edf6b80a85145e5061acd29bc5a4e349b14f8557a7033e23b066b7ce2409d973\n"
+    );
+
+    assert!(run_err(
+        &["derive", "registered", "--context", CONTEXT, "--zip", "1"],
+        SEED,
+        1
+    )
+    .contains("at least one element"));
+    assert!(run_err(
+        &[
+            "derive",
+            "registered",
+            "--context",
+            CONTEXT,
+            "--zip",
+            "65536",
+            "1'"
+        ],
+        SEED,
+        1
+    )
+    .contains("ZIP number"));
+}
+
+#[test]
+fn path() {
+    assert_eq!(
+        run_ok(&["path", "32h/133'/0':00ff"], ""),
+        "path: m/32'/133'/0':00ff\ndepth: 3\nindices: 2147483680 2147483781 2147483648\n"
+    );
+    assert!(run_err(&["path", "m/1'/2"], "", 1).contains("path element 1 is not hardened"));
+    run_err(&["path"], "", 2);
+}
+
+#[test]
+fn diversifier_index() {
+    let expected = "decimal: 258\nbytes: 0201000000000000000000\n";
+    assert_eq!(run_ok(&["diversifier-index", "258"], ""), expected);
+    assert_eq!(
+        run_ok(
+            &["diversifier-index", "--bytes", "0201000000000000000000"],
+            ""
+        ),
+        expected
+    );
+    assert_eq!(
+        run_ok(&["diversifier-index", "309485009821345068724781055"], ""),
+        "decimal: 309485009821345068724781055\nbytes: ffffffffffffffffffffff\n"
+    );
+
+    run_err(&["diversifier-index", "309485009821345068724781056"], "", 1);
+    run_err(&["diversifier-index", "+258"], "", 1);
+    run_err(&["diversifier-index", "0x0201000000000000000000"], "", 1);
+    run_err(
+        &["diversifier-index", "--bytes", "0x0201000000000000000000"],
+        "",
+        1,
+    );
+    run_err(&["diversifier-index", "--bytes", "0201"], "", 1);
+    run_err(&["diversifier-index"], "", 2);
+}
+
+#[test]
+fn secrets_are_not_echoed() {
+    // An invalid seed that is otherwise secret-looking.
+    let seed = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdefg";
+    let stderr = run_err(&["fingerprint"], seed, 1);
+    assert!(stderr.contains("not a valid hex string"));
+    assert!(!stderr.contains("0123456789abcdef"));
+
+    let stderr = run_err(&["fingerprint"], &SEED[..62], 1);
+    assert!(stderr.contains("between 32 and 252 bytes"));
+    assert!(!stderr.contains(&SEED[..62]));
+
+    for output in [
+        run_ok(&["fingerprint"], SEED),
+        run_ok(&["derive", "arbitrary", "--context", CONTEXT, "m/1'"], SEED),
+    ] {
+        assert!(!output.contains(SEED));
+    }
+}
+
+#[test]
+fn usage_errors() {
+    run_err(&[], "", 2);
+    run_err(&["frobnicate"], "", 2);
+    run_err(&["derive"], "", 2);
+    run_err(&["derive", "other"], "", 2);
+    run_err(&["derive", "arbitrary", "m/1'"], SEED, 2);
+    run_err(&["fingerprint", "--unknown"], SEED, 2);
+    run_err(&["fingerprint", "--seed-file"], SEED, 2);
+    run_err(&["fingerprint", "extra"], SEED, 2);
+    assert!(run_ok(&["--help"], "").starts_with("Usage:"));
+}
@@ -0,0 +1,278 @@
+#ifndef ZIP32_H
+#define ZIP32_H
+
+/* Warning, this file is autogenerated by cbindgen. Don't modify this manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The length of a seed fingerprint, in bytes.
+ */
+#define ZIP32_SEED_FINGERPRINT_LEN 32
+
+/**
+ * The length of the Bech32m encoding of a seed fingerprint, excluding the terminating
+ * NUL byte.
+ */
+#define ZIP32_SEED_FINGERPRINT_ENCODED_LEN 70
+
+/**
+ * The length of an extended secret key, in bytes. This is the 32-byte secret key
+ * followed by the 32-byte chain code.
+ */
+#define ZIP32_KEY_LEN 64
+
+/**
+ * The length of a registered cryptovalue, in bytes.
+ */
+#define ZIP32_CRYPTOVALUE_LEN 64
+
+/**
+ * The length of a diversifier index, in bytes.
+ */
+#define ZIP32_DIVERSIFIER_INDEX_LEN 11
+
+/**
+ * The result of a call to one of the functions in this library.
+ */
+typedef enum {
+  /**
+   * The call succeeded.
+   */
+  ZIP32_STATUS_OK = 0,
+  /**
+   * A required pointer was null.
+   */
+  ZIP32_STATUS_NULL_POINTER = 1,
+  /**
+   * A fixed-size input buffer had the wrong length.
+   */
+  ZIP32_STATUS_INVALID_LENGTH = 2,
+  /**
+   * An output buffer was too small for the value to be written to it.
+   */
+  ZIP32_STATUS_BUFFER_TOO_SMALL = 3,
+  /**
+   * A context string was empty, or longer than 252 bytes.
+   */
+  ZIP32_STATUS_INVALID_CONTEXT_STRING = 4,
+  /**
+   * A seed was shorter than 32 bytes, or longer than 252 bytes.
+   */
+  ZIP32_STATUS_INVALID_SEED = 5,
+  /**
+   * A child index did not have the hardened bit set.
+   */
+  ZIP32_STATUS_INVALID_CHILD_INDEX = 6,
+  /**
+   * A registered subpath was empty, where a non-empty subpath is required.
+   */
+  ZIP32_STATUS_SUBPATH_EMPTY = 7,
+  /**
+   * A string was not a valid encoding of the expected value.
+   */
+  ZIP32_STATUS_INVALID_ENCODING = 8,
+  /**
+   * A value was out of the range of the requested type, or an increment overflowed.
+   */
+  ZIP32_STATUS_OUT_OF_RANGE = 9,
+} Zip32Status;
+
+/**
+ * An element of a registered key derivation subpath.
+ */
+typedef struct {
+  /**
+   * The child index, including the hardened bit (which must be set).
+   */
+  uint32_t child_index;
+  /**
+   * The tag, which may be null if `tag_len` is zero.
+   */
+  const uint8_t *tag;
+  /**
+   * The length of the tag, in bytes.
+   */
+  size_t tag_len;
+} Zip32PathElement;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Computes the fingerprint of a seed.
+ *
+ * Returns [`Zip32Status::InvalidSeed`] if the seed is shorter than 32 bytes or longer
+ * than 252 bytes.
+ *
+ * # Safety
+ *
+ * - `seed` must be valid for reads of `seed_len` bytes.
+ * - `fp_out` must be valid for writes of `fp_out_len` bytes, which must be at least
+ *   [`ZIP32_SEED_FINGERPRINT_LEN`].
+ */
+Zip32Status zip32_seed_fingerprint(const uint8_t *seed,
+                                   size_t seed_len,
+                                   uint8_t *fp_out,
+                                   size_t fp_out_len);
+
+/**
+ * Encodes a seed fingerprint as a NUL-terminated Bech32m string.
+ *
+ * # Safety
+ *
+ * - `fp` must be valid for reads of `fp_len` bytes, which must be
+ *   [`ZIP32_SEED_FINGERPRINT_LEN`].
+ * - `encoded_out` must be valid for writes of `encoded_out_len` bytes, which must be at
+ *   least [`ZIP32_SEED_FINGERPRINT_ENCODED_LEN`]` + 1`.
+ */
+Zip32Status zip32_seed_fingerprint_encode(const uint8_t *fp,
+                                          size_t fp_len,
+                                          char *encoded_out,
+                                          size_t encoded_out_len);
+
+/**
+ * Decodes a Bech32m-encoded seed fingerprint.
+ *
+ * The encoding is given as a string of `encoded_len` bytes, which need not be
+ * NUL-terminated. Returns [`Zip32Status::InvalidEncoding`] if it is not a valid seed
+ * fingerprint.
+ *
+ * # Safety
+ *
+ * - `encoded` must be valid for reads of `encoded_len` bytes.
+ * - `fp_out` must be valid for writes of `fp_out_len` bytes, which must be at least
+ *   [`ZIP32_SEED_FINGERPRINT_LEN`].
+ */
+Zip32Status zip32_seed_fingerprint_decode(const char *encoded,
+                                          size_t encoded_len,
+                                          uint8_t *fp_out,
+                                          size_t fp_out_len);
+
+/**
+ * Derives an ad-hoc extended secret key at the given path, as specified in
+ * [ZIP 32: Ad-hoc key derivation (deprecated)][adhoc].
+ *
+ * The path is given as `path_len` child indices, each of which must have the hardened
+ * bit set. The key is written to `key_out` as the secret key followed by the chain code.
+ *
+ * [adhoc]: https://zips.z.cash/zip-0032#specification-ad-hoc-key-derivation-deprecated
+ *
+ * # Safety
+ *
+ * - `context_string` must be valid for reads of `context_string_len` bytes.
+ * - `seed` must be valid for reads of `seed_len` bytes.
+ * - `path` must be valid for reads of `path_len` child indices, or null if `path_len`
+ *   is zero.
+ * - `key_out` must be valid for writes of `key_out_len` bytes, which must be at least
+ *   [`ZIP32_KEY_LEN`].
+ */
+Zip32Status zip32_arbitrary_derive_key(const uint8_t *context_string,
+                                       size_t context_string_len,
+                                       const uint8_t *seed,
+                                       size_t seed_len,
+                                       const uint32_t *path,
+                                       size_t path_len,
+                                       uint8_t *key_out,
+                                       size_t key_out_len);
+
+/**
+ * Derives a registered extended secret key at the given subpath of the subtree for the
+ * given ZIP, as specified in [ZIP 32: Registered key derivation][regkd].
+ *
+ * The subpath may be empty, in which case the root of the ZIP's subtree is derived. The
+ * key is written to `key_out` as the secret key followed by the chain code.
+ *
+ * [regkd]: https://zips.z.cash/zip-0032#specification-registered-key-derivation
+ *
+ * # Safety
+ *
+ * - `context_string` must be valid for reads of `context_string_len` bytes.
+ * - `seed` must be valid for reads of `seed_len` bytes.
+ * - `subpath` must be valid for reads of `subpath_len` path elements, or null if
+ *   `subpath_len` is zero, and each element's tag must be valid for reads of its
+ *   `tag_len` bytes.
+ * - `key_out` must be valid for writes of `key_out_len` bytes, which must be at least
+ *   [`ZIP32_KEY_LEN`].
+ */
+Zip32Status zip32_registered_derive_key(const uint8_t *context_string,
+                                        size_t context_string_len,
+                                        const uint8_t *seed,
+                                        size_t seed_len,
+                                        uint16_t zip_number,
+                                        const Zip32PathElement *subpath,
+                                        size_t subpath_len,
+                                        uint8_t *key_out,
+                                        size_t key_out_len);
+
+/**
+ * Derives a 64-byte registered cryptovalue at the given non-empty subpath of the subtree
+ * for the given ZIP, as specified in [ZIP 32: Registered key derivation][regkd].
+ *
+ * [regkd]: https://zips.z.cash/zip-0032#specification-registered-key-derivation
+ *
+ * # Safety
+ *
+ * - `context_string` must be valid for reads of `context_string_len` bytes.
+ * - `seed` must be valid for reads of `seed_len` bytes.
+ * - `subpath` must be valid for reads of `subpath_len` path elements, and each element's
+ *   tag must be valid for reads of its `tag_len` bytes.
+ * - `cryptovalue_out` must be valid for writes of `cryptovalue_out_len` bytes, which
+ *   must be at least [`ZIP32_CRYPTOVALUE_LEN`].
+ */
+Zip32Status zip32_registered_cryptovalue(const uint8_t *context_string,
+                                         size_t context_string_len,
+                                         const uint8_t *seed,
+                                         size_t seed_len,
+                                         uint16_t zip_number,
+                                         const Zip32PathElement *subpath,
+                                         size_t subpath_len,
+                                         uint8_t *cryptovalue_out,
+                                         size_t cryptovalue_out_len);
+
+/**
+ * Writes the little-endian encoding of the diversifier index with the given value.
+ *
+ * # Safety
+ *
+ * `di_out` must be valid for writes of `di_out_len` bytes, which must be at least
+ * [`ZIP32_DIVERSIFIER_INDEX_LEN`].
+ */
+Zip32Status zip32_diversifier_index_from_u64(uint64_t value, uint8_t *di_out, size_t di_out_len);
+
+/**
+ * Returns the value of a little-endian diversifier index as a `uint64_t`.
+ *
+ * Returns [`Zip32Status::OutOfRange`] if the value is not less than 2^64.
+ *
+ * # Safety
+ *
+ * - `di` must be valid for reads of `di_len` bytes, which must be
+ *   [`ZIP32_DIVERSIFIER_INDEX_LEN`].
+ * - `value_out` must be valid for writes of a `uint64_t`.
+ */
+Zip32Status zip32_diversifier_index_to_u64(const uint8_t *di, size_t di_len, uint64_t *value_out);
+
+/**
+ * Increments a little-endian diversifier index in place.
+ *
+ * Returns [`Zip32Status::OutOfRange`] (leaving the index unchanged) if the increment
+ * would overflow.
+ *
+ * # Safety
+ *
+ * `di` must be valid for reads and writes of `di_len` bytes, which must be
+ * [`ZIP32_DIVERSIFIER_INDEX_LEN`].
+ */
+Zip32Status zip32_diversifier_index_increment(uint8_t *di, size_t di_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ZIP32_H */
@@ -0,0 +1,495 @@
+//! C bindings for the [`zip32`] crate.
+//!
+//! The C header for these bindings is `include/zip32.h`, which is generated with
+//! [`cbindgen`] from the root of this crate:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/zip32.h
+//! ```
+//!
+//! CI regenerates the header in the same way, and fails if it differs from the
+//! checked-in copy.
+//!
+//! Every function returns a [`Zip32Status`], and only writes to its outputs if it returns
+//! [`Zip32Status::Ok`]. Byte buffers are passed as a pointer and a length:
+//!
+//! - A fixed-size input buffer must have exactly the expected length.
+//! - A null pointer is permitted for an empty variable-length input buffer.
+//! - An output buffer must be at least as long as the value written to it.
+//!
+//! [`cbindgen`]: https://github.com/mozilla/cbindgen
+
+#![deny(missing_docs)]
+
+use std::{os::raw::c_char, slice};
+
+use zeroize::Zeroizing;
+use zip32::{
+    arbitrary,
+    fingerprint::SeedFingerprint,
+    registered::{self, DerivationError, PathElement},
+    ChainCode, ChildIndex, DiversifierIndex,
+};
+
+/// The length of a seed fingerprint, in bytes.
+pub const ZIP32_SEED_FINGERPRINT_LEN: usize = 32;
+
+/// The length of the Bech32m encoding of a seed fingerprint, excluding the terminating
+/// NUL byte.
+pub const ZIP32_SEED_FINGERPRINT_ENCODED_LEN: usize = 70;
+
+/// The length of an extended secret key, in bytes. This is the 32-byte secret key
+/// followed by the 32-byte chain code.
+pub const ZIP32_KEY_LEN: usize = 64;
+
+/// The length of a registered cryptovalue, in bytes.
+pub const ZIP32_CRYPTOVALUE_LEN: usize = 64;
+
+/// The length of a diversifier index, in bytes.
+pub const ZIP32_DIVERSIFIER_INDEX_LEN: usize = 11;
+
+/// The result of a call to one of the functions in this library.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Zip32Status {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// A fixed-size input buffer had the wrong length.
+    InvalidLength = 2,
+    /// An output buffer was too small for the value to be written to it.
+    BufferTooSmall = 3,
+    /// A context string was empty, or longer than 252 bytes.
+    InvalidContextString = 4,
+    /// A seed was shorter than 32 bytes, or longer than 252 bytes.
+    InvalidSeed = 5,
+    /// A child index did not have the hardened bit set.
+    InvalidChildIndex = 6,
+    /// A registered subpath was empty, where a non-empty subpath is required.
+    SubpathEmpty = 7,
+    /// A string was not a valid encoding of the expected value.
+    InvalidEncoding = 8,
+    /// A value was out of the range of the requested type, or an increment overflowed.
+    OutOfRange = 9,
+}
+
+impl From<DerivationError> for Zip32Status {
+    fn from(e: DerivationError) -> Self {
+        match e {
+            DerivationError::SeedInvalid => Zip32Status::InvalidSeed,
+            DerivationError::ContextStringInvalid => Zip32Status::InvalidContextString,
+            DerivationError::SubpathEmpty => Zip32Status::SubpathEmpty,
+        }
+    }
+}
+
+/// An element of a registered key derivation subpath.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Zip32PathElement {
+    /// The child index, including the hardened bit (which must be set).
+    pub child_index: u32,
+    /// The tag, which may be null if `tag_len` is zero.
+    pub tag: *const u8,
+    /// The length of the tag, in bytes.
+    pub tag_len: usize,
+}
+
+/// Runs `f`, converting its result into a status code.
+fn status(f: impl FnOnce() -> Result<(), Zip32Status>) -> Zip32Status {
+    match f() {
+        Ok(()) => Zip32Status::Ok,
+        Err(e) => e,
+    }
+}
+
+/// Returns the variable-length input buffer at `ptr`.
+///
+/// # Safety
+///
+/// If `len` is non-zero, `ptr` must either be null or valid for reads of `len` bytes.
+unsafe fn input<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], Zip32Status> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(Zip32Status::NullPointer)
+    } else {
+        Ok(slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Returns the fixed-size input buffer at `ptr`.
+///
+/// # Safety
+///
+/// `ptr` must either be null or valid for reads of `len` bytes.
+unsafe fn fixed_input<'a, const N: usize>(
+    ptr: *const u8,
+    len: usize,
+) -> Result<&'a [u8; N], Zip32Status> {
+    if ptr.is_null() {
+        Err(Zip32Status::NullPointer)
+    } else if len != N {
+        Err(Zip32Status::InvalidLength)
+    } else {
+        Ok(&*(ptr as *const [u8; N]))
+    }
+}
+
+/// Returns the first `N` bytes of the output buffer at `ptr`.
+///
+/// # Safety
+///
+/// `ptr` must either be null or valid for writes of `len` bytes.
+unsafe fn output<'a, T, const N: usize>(
+    ptr: *mut T,
+    len: usize,
+) -> Result<&'a mut [T; N], Zip32Status> {
+    if ptr.is_null() {
+        Err(Zip32Status::NullPointer)
+    } else if len < N {
+        Err(Zip32Status::BufferTooSmall)
+    } else {
+        Ok(&mut *(ptr as *mut [T; N]))
+    }
+}
+
+/// Writes an extended secret key to `out`.
+fn write_key(out: &mut [u8; ZIP32_KEY_LEN], sk: &[u8; 32], chain_code: &ChainCode) {
+    out[..32].copy_from_slice(sk);
+    out[32..].copy_from_slice(chain_code.as_bytes());
+}
+
+/// Computes the fingerprint of a seed.
+///
+/// Returns [`Zip32Status::InvalidSeed`] if the seed is shorter than 32 bytes or longer
+/// than 252 bytes.
+///
+/// # Safety
+///
+/// - `seed` must be valid for reads of `seed_len` bytes.
+/// - `fp_out` must be valid for writes of `fp_out_len` bytes, which must be at least
+///   [`ZIP32_SEED_FINGERPRINT_LEN`].
+#[no_mangle]
+pub unsafe extern "C" fn zip32_seed_fingerprint(
+    seed: *const u8,
+    seed_len: usize,
+    fp_out: *mut u8,
+    fp_out_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let seed = input(seed, seed_len)?;
+        let fp_out = output::<_, ZIP32_SEED_FINGERPRINT_LEN>(fp_out, fp_out_len)?;
+        let fp = SeedFingerprint::from_seed(seed).ok_or(Zip32Status::InvalidSeed)?;
+        *fp_out = fp.to_bytes();
+        Ok(())
+    })
+}
+
+/// Encodes a seed fingerprint as a NUL-terminated Bech32m string.
+///
+/// # Safety
+///
+/// - `fp` must be valid for reads of `fp_len` bytes, which must be
+///   [`ZIP32_SEED_FINGERPRINT_LEN`].
+/// - `encoded_out` must be valid for writes of `encoded_out_len` bytes, which must be at
+///   least [`ZIP32_SEED_FINGERPRINT_ENCODED_LEN`]` + 1`.
+#[no_mangle]
+pub unsafe extern "C" fn zip32_seed_fingerprint_encode(
+    fp: *const u8,
+    fp_len: usize,
+    encoded_out: *mut c_char,
+    encoded_out_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let fp = fixed_input::<ZIP32_SEED_FINGERPRINT_LEN>(fp, fp_len)?;
+        let encoded_out =
+            output::<_, { ZIP32_SEED_FINGERPRINT_ENCODED_LEN + 1 }>(encoded_out, encoded_out_len)?;
+        let encoded = SeedFingerprint::from_bytes(*fp).to_string();
+        for (dest, src) in encoded_out.iter_mut().zip(encoded.bytes().chain(Some(0))) {
+            *dest = src as c_char;
+        }
+        Ok(())
+    })
+}
+
+/// Decodes a Bech32m-encoded seed fingerprint.
+///
+/// The encoding is given as a string of `encoded_len` bytes, which need not be
+/// NUL-terminated. Returns [`Zip32Status::InvalidEncoding`] if it is not a valid seed
+/// fingerprint.
+///
+/// # Safety
+///
+/// - `encoded` must be valid for reads of `encoded_len` bytes.
+/// - `fp_out` must be valid for writes of `fp_out_len` bytes, which must be at least
+///   [`ZIP32_SEED_FINGERPRINT_LEN`].
+#[no_mangle]
+pub unsafe extern "C" fn zip32_seed_fingerprint_decode(
+    encoded: *const c_char,
+    encoded_len: usize,
+    fp_out: *mut u8,
+    fp_out_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let encoded = input(encoded as *const u8, encoded_len)?;
+        let fp_out = output::<_, ZIP32_SEED_FINGERPRINT_LEN>(fp_out, fp_out_len)?;
+        let fp = std::str::from_utf8(encoded)
+            .ok()
+            .and_then(|s| s.parse::<SeedFingerprint>().ok())
+            .ok_or(Zip32Status::InvalidEncoding)?;
+        *fp_out = fp.to_bytes();
+        Ok(())
+    })
+}
+
+/// Derives an ad-hoc extended secret key at the given path, as specified in
+/// [ZIP 32: Ad-hoc key derivation (deprecated)][adhoc].
+///
+/// The path is given as `path_len` child indices, each of which must have the hardened
+/// bit set. The key is written to `key_out` as the secret key followed by the chain code.
+///
+/// [adhoc]: https://zips.z.cash/zip-0032#specification-ad-hoc-key-derivation-deprecated
+///
+/// # Safety
+///
+/// - `context_string` must be valid for reads of `context_string_len` bytes.
+/// - `seed` must be valid for reads of `seed_len` bytes.
+/// - `path` must be valid for reads of `path_len` child indices, or null if `path_len`
+///   is zero.
+/// - `key_out` must be valid for writes of `key_out_len` bytes, which must be at least
+///   [`ZIP32_KEY_LEN`].
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn zip32_arbitrary_derive_key(
+    context_string: *const u8,
+    context_string_len: usize,
+    seed: *const u8,
+    seed_len: usize,
+    path: *const u32,
+    path_len: usize,
+    key_out: *mut u8,
+    key_out_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let context_string = input(context_string, context_string_len)?;
+        let seed = input(seed, seed_len)?;
+        let path = input(path, path_len)?
+            .iter()
+            .map(|i| ChildIndex::from_index(*i).ok_or(Zip32Status::InvalidChildIndex))
+            .collect::<Result<Vec<_>, _>>()?;
+        let key_out = output::<_, ZIP32_KEY_LEN>(key_out, key_out_len)?;
+        registered::check_master_inputs(context_string, seed)?;
+
+        let key = arbitrary::SecretKey::from_path(context_string, seed, &path);
+        write_key(key_out, key.data(), key.chain_code());
+        Ok(())
+    })
+}
+
+/// Converts a C subpath into registered path elements.
+///
+/// # Safety
+///
+/// `subpath` must be valid for reads of `subpath_len` elements, or null if `subpath_len`
+/// is zero, and each element's tag must be valid for reads of its `tag_len` bytes.
+unsafe fn registered_subpath<'a>(
+    subpath: *const Zip32PathElement,
+    subpath_len: usize,
+) -> Result<Vec<PathElement<'a>>, Zip32Status> {
+    input(subpath, subpath_len)?
+        .iter()
+        .map(|elem| {
+            let child_index =
+                ChildIndex::from_index(elem.child_index).ok_or(Zip32Status::InvalidChildIndex)?;
+            Ok(PathElement::new(
+                child_index,
+                input(elem.tag, elem.tag_len)?,
+            ))
+        })
+        .collect()
+}
+
+/// Derives a registered extended secret key at the given subpath of the subtree for the
+/// given ZIP, as specified in [ZIP 32: Registered key derivation][regkd].
+///
+/// The subpath may be empty, in which case the root of the ZIP's subtree is derived. The
+/// key is written to `key_out` as the secret key followed by the chain code.
+///
+/// [regkd]: https://zips.z.cash/zip-0032#specification-registered-key-derivation
+///
+/// # Safety
+///
+/// - `context_string` must be valid for reads of `context_string_len` bytes.
+/// - `seed` must be valid for reads of `seed_len` bytes.
+/// - `subpath` must be valid for reads of `subpath_len` path elements, or null if
+///   `subpath_len` is zero, and each element's tag must be valid for reads of its
+///   `tag_len` bytes.
+/// - `key_out` must be valid for writes of `key_out_len` bytes, which must be at least
+///   [`ZIP32_KEY_LEN`].
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn zip32_registered_derive_key(
+    context_string: *const u8,
+    context_string_len: usize,
+    seed: *const u8,
+    seed_len: usize,
+    zip_number: u16,
+    subpath: *const Zip32PathElement,
+    subpath_len: usize,
+    key_out: *mut u8,
+    key_out_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let context_string = input(context_string, context_string_len)?;
+        let seed = input(seed, seed_len)?;
+        let subpath = registered_subpath(subpath, subpath_len)?;
+        let key_out = output::<_, ZIP32_KEY_LEN>(key_out, key_out_len)?;
+
+        let key = registered::SecretKey::from_subpath(context_string, seed, zip_number, &subpath)?;
+        write_key(key_out, key.data(), key.chain_code());
+        Ok(())
+    })
+}
+
+/// Derives a 64-byte registered cryptovalue at the given non-empty subpath of the subtree
+/// for the given ZIP, as specified in [ZIP 32: Registered key derivation][regkd].
+///
+/// [regkd]: https://zips.z.cash/zip-0032#specification-registered-key-derivation
+///
+/// # Safety
+///
+/// - `context_string` must be valid for reads of `context_string_len` bytes.
+/// - `seed` must be valid for reads of `seed_len` bytes.
+/// - `subpath` must be valid for reads of `subpath_len` path elements, and each element's
+///   tag must be valid for reads of its `tag_len` bytes.
+/// - `cryptovalue_out` must be valid for writes of `cryptovalue_out_len` bytes, which
+///   must be at least [`ZIP32_CRYPTOVALUE_LEN`].
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn zip32_registered_cryptovalue(
+    context_string: *const u8,
+    context_string_len: usize,
+    seed: *const u8,
+    seed_len: usize,
+    zip_number: u16,
+    subpath: *const Zip32PathElement,
+    subpath_len: usize,
+    cryptovalue_out: *mut u8,
+    cryptovalue_out_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let context_string = input(context_string, context_string_len)?;
+        let seed = input(seed, seed_len)?;
+        let subpath = registered_subpath(subpath, subpath_len)?;
+        let cryptovalue_out =
+            output::<_, ZIP32_CRYPTOVALUE_LEN>(cryptovalue_out, cryptovalue_out_len)?;
+
+        let cryptovalue = Zeroizing::new(registered::cryptovalue_from_subpath(
+            context_string,
+            seed,
+            zip_number,
+            &subpath,
+        )?);
+        cryptovalue_out.copy_from_slice(&cryptovalue[..]);
+        Ok(())
+    })
+}
+
+/// Writes the little-endian encoding of the diversifier index with the given value.
+///
+/// # Safety
+///
+/// `di_out` must be valid for writes of `di_out_len` bytes, which must be at least
+/// [`ZIP32_DIVERSIFIER_INDEX_LEN`].
+#[no_mangle]
+pub unsafe extern "C" fn zip32_diversifier_index_from_u64(
+    value: u64,
+    di_out: *mut u8,
+    di_out_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let di_out = output::<_, ZIP32_DIVERSIFIER_INDEX_LEN>(di_out, di_out_len)?;
+        *di_out = *DiversifierIndex::from(value).as_bytes();
+        Ok(())
+    })
+}
+
+/// Returns the value of a little-endian diversifier index as a `uint64_t`.
+///
+/// Returns [`Zip32Status::OutOfRange`] if the value is not less than 2^64.
+///
+/// # Safety
+///
+/// - `di` must be valid for reads of `di_len` bytes, which must be
+///   [`ZIP32_DIVERSIFIER_INDEX_LEN`].
+/// - `value_out` must be valid for writes of a `uint64_t`.
+#[no_mangle]
+pub unsafe extern "C" fn zip32_diversifier_index_to_u64(
+    di: *const u8,
+    di_len: usize,
+    value_out: *mut u64,
+) -> Zip32Status {
+    status(|| {
+        let di = DiversifierIndex::from(*fixed_input::<ZIP32_DIVERSIFIER_INDEX_LEN>(di, di_len)?);
+        if value_out.is_null() {
+            return Err(Zip32Status::NullPointer);
+        }
+        *value_out = u64::try_from(di).map_err(|_| Zip32Status::OutOfRange)?;
+        Ok(())
+    })
+}
+
+/// Increments a little-endian diversifier index in place.
+///
+/// Returns [`Zip32Status::OutOfRange`] (leaving the index unchanged) if the increment
+/// would overflow.
+///
+/// # Safety
+///
+/// `di` must be valid for reads and writes of `di_len` bytes, which must be
+/// [`ZIP32_DIVERSIFIER_INDEX_LEN`].
+#[no_mangle]
+pub unsafe extern "C" fn zip32_diversifier_index_increment(
+    di: *mut u8,
+    di_len: usize,
+) -> Zip32Status {
+    status(|| {
+        let mut next =
+            DiversifierIndex::from(*fixed_input::<ZIP32_DIVERSIFIER_INDEX_LEN>(di, di_len)?);
+        next.increment().map_err(|_| Zip32Status::OutOfRange)?;
+        *output::<_, ZIP32_DIVERSIFIER_INDEX_LEN>(di, di_len)? = *next.as_bytes();
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    /// Checks that the checked-in header declares every exported item, as a reminder to
+    /// regenerate it with `cbindgen` when the API changes.
+    #[test]
+    fn header_is_up_to_date() {
+        let source = include_str!("lib.rs");
+        let header = include_str!("../include/zip32.h");
+
+        for line in source.lines() {
+            if let Some(rest) = line.strip_prefix("pub unsafe extern \"C\" fn ") {
+                let name = &rest[..rest.find('(').unwrap()];
+                assert!(
+                    header.contains(&format!("Zip32Status {}(", name)),
+                    "{} is missing from the header",
+                    name
+                );
+            } else if let Some(rest) = line.strip_prefix("pub const ") {
+                let (name, rest) = rest.split_once(": usize = ").unwrap();
+                let value = rest.trim_end_matches(';');
+                assert!(
+                    header.contains(&format!("#define {} {}\n", name, value)),
+                    "{} is missing from the header",
+                    name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+/*
+ * Tests for the zip32 C API, using the test vectors from the zip32 crate.
+ *
+ * Exits with a non-zero status if any check fails.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "zip32.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                  \
+  do {                                                               \
+    if (!(cond)) {                                                   \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+              #cond);                                                \
+      failures++;                                                    \
+    }                                                                \
+  } while (0)
+
+#define CHECK_STATUS(call, expected) CHECK((call) == (expected))
+
+static const char CONTEXT[] = "Zcash test vectors";
+
+/* Decodes a hex string into `out`, which must be large enough. */
+static void from_hex(const char *hex, uint8_t *out) {
+  size_t i;
+  for (i = 0; hex[2 * i] != '\0'; i++) {
+    unsigned int byte;
+    sscanf(&hex[2 * i], "%2x", &byte);
+    out[i] = (uint8_t)byte;
+  }
+}
+
+static int equals_hex(const uint8_t *bytes, size_t len, const char *hex) {
+  uint8_t expected[256];
+  if (strlen(hex) != 2 * len) {
+    return 0;
+  }
+  from_hex(hex, expected);
+  return memcmp(bytes, expected, len) == 0;
+}
+
+static void test_seed_fingerprint(const uint8_t *seed) {
+  static const char ENCODED[] =
+      "zip32seedfp1mmlkqnpyvug0w9mdatgz4f6x7t7c65uf7urj24kuk42lm0j78t3sne2h0z";
+  uint8_t fp[ZIP32_SEED_FINGERPRINT_LEN];
+  uint8_t decoded[ZIP32_SEED_FINGERPRINT_LEN];
+  char encoded[ZIP32_SEED_FINGERPRINT_ENCODED_LEN + 1];
+
+  CHECK_STATUS(zip32_seed_fingerprint(seed, 32, fp, sizeof(fp)), ZIP32_STATUS_OK);
+  CHECK(equals_hex(fp, sizeof(fp),
+                   "deff604c246710f7176dead02aa746f2fd8d5389f7072556dcb555fdbe5e3ae3"));
+
+  CHECK_STATUS(zip32_seed_fingerprint_encode(fp, sizeof(fp), encoded, sizeof(encoded)),
+               ZIP32_STATUS_OK);
+  CHECK(strcmp(encoded, ENCODED) == 0);
+  CHECK_STATUS(
+      zip32_seed_fingerprint_decode(encoded, strlen(encoded), decoded, sizeof(decoded)),
+      ZIP32_STATUS_OK);
+  CHECK(memcmp(decoded, fp, sizeof(fp)) == 0);
+
+  /* Invalid inputs are rejected. */
+  CHECK_STATUS(zip32_seed_fingerprint(seed, 31, fp, sizeof(fp)), ZIP32_STATUS_INVALID_SEED);
+  CHECK_STATUS(zip32_seed_fingerprint(NULL, 32, fp, sizeof(fp)), ZIP32_STATUS_NULL_POINTER);
+  CHECK_STATUS(zip32_seed_fingerprint(seed, 32, NULL, sizeof(fp)), ZIP32_STATUS_NULL_POINTER);
+  CHECK_STATUS(zip32_seed_fingerprint(seed, 32, fp, sizeof(fp) - 1),
+               ZIP32_STATUS_BUFFER_TOO_SMALL);
+  CHECK_STATUS(zip32_seed_fingerprint_encode(fp, sizeof(fp), encoded, sizeof(encoded) - 1),
+               ZIP32_STATUS_BUFFER_TOO_SMALL);
+  CHECK_STATUS(zip32_seed_fingerprint_encode(fp, sizeof(fp) - 1, encoded, sizeof(encoded)),
+               ZIP32_STATUS_INVALID_LENGTH);
+  CHECK_STATUS(
+      zip32_seed_fingerprint_decode(ENCODED, strlen(ENCODED) - 1, decoded, sizeof(decoded)),
+      ZIP32_STATUS_INVALID_ENCODING);
+  CHECK_STATUS(zip32_seed_fingerprint_decode("", 0, decoded, sizeof(decoded)),
+               ZIP32_STATUS_INVALID_ENCODING);
+}
+
+static void test_arbitrary(const uint8_t *seed) {
+  const uint32_t path[] = {0x80000001, 0x80000002, 0x80000003};
+  const uint32_t unhardened[] = {0x80000001, 2};
+  uint8_t key[ZIP32_KEY_LEN];
+
+  CHECK_STATUS(zip32_arbitrary_derive_key((const uint8_t *)CONTEXT, strlen(CONTEXT), seed, 32,
+                                          path, 3, key, sizeof(key)),
+               ZIP32_STATUS_OK);
+  CHECK(equals_hex(key, 32, "fc4b6e93b0e42f7a762ca0c6522ccd1045cab506b372452af7306c87389ab62c"));
+  CHECK(equals_hex(key + 32, 32,
+                   "e89bf2ed73f5e0887542e36793fac82c508ab5d99198578227b241fbac198429"));
+
+  /* The master key is at the empty path. */
+  CHECK_STATUS(zip32_arbitrary_derive_key((const uint8_t *)CONTEXT, strlen(CONTEXT), seed, 32,
+                                          NULL, 0, key, sizeof(key)),
+               ZIP32_STATUS_OK);
+  CHECK(equals_hex(key, 32, "e9da8806409dc3c3ebd1fc2a71c879c13dd7aa93ede803bf1a83414b9d3b158a"));
+
+  CHECK_STATUS(zip32_arbitrary_derive_key((const uint8_t *)CONTEXT, strlen(CONTEXT), seed, 32,
+                                          unhardened, 2, key, sizeof(key)),
+               ZIP32_STATUS_INVALID_CHILD_INDEX);
+  CHECK_STATUS(
+      zip32_arbitrary_derive_key(NULL, 0, seed, 32, path, 3, key, sizeof(key)),
+      ZIP32_STATUS_INVALID_CONTEXT_STRING);
+  CHECK_STATUS(zip32_arbitrary_derive_key((const uint8_t *)CONTEXT, strlen(CONTEXT), seed, 253,
+                                          path, 3, key, sizeof(key)),
+               ZIP32_STATUS_INVALID_SEED);
+}
+
+static void test_registered(const uint8_t *seed) {
+  static const char TAG[] = "trans rights are human rights";
+  static const uint8_t LONG_TAG[300] = {0};
+  Zip32PathElement subpath[2];
+  uint8_t key[ZIP32_KEY_LEN];
+  uint8_t cryptovalue[ZIP32_CRYPTOVALUE_LEN];
+
+  subpath[0].child_index = 0x80000002;
+  subpath[0].tag = (const uint8_t *)TAG;
+  subpath[0].tag_len = strlen(TAG);
+  subpath[1].child_index = 0x80000003;
+  subpath[1].tag = NULL;
+  subpath[1].tag_len = 0;
+
+  CHECK_STATUS(zip32_registered_derive_key((const uint8_t *)CONTEXT, strlen(CONTEXT), seed, 32,
+                                           1, subpath, 1, key, sizeof(key)),
+               ZIP32_STATUS_OK);
+  CHECK(equals_hex(key, 32, "02dc25cc40310eed08b028e07fae9adbee2fbe56a4694def0401e656dfae0211"));
+  CHECK(equals_hex(key + 32, 32,
+                   "d8f9d8a1f81d1b5d5506b5ff942d2ff3dae7a63f57d6b8c7fbe58149823cc6ec"));
+
+  /* The root of the ZIP's subtree is at the empty subpath. */
+  CHECK_STATUS(zip32_registered_derive_key((const uint8_t *)CONTEXT, strlen(CONTEXT), seed, 32,
+                                           1, NULL, 0, key, sizeof(key)),
+               ZIP32_STATUS_OK);
+  CHECK(equals_hex(key, 32, "53a71507e6dfda588bc1e138c2657c9269e55f5d9b99e3887c134008193a2f47"));
+
+  CHECK_STATUS(zip32_registered_cryptovalue((const uint8_t *)CONTEXT, strlen(CONTEXT), seed,
+                                            32, 1, subpath, 2, cryptovalue,
+                                            sizeof(cryptovalue)),
+               ZIP32_STATUS_OK);
+  CHECK(equals_hex(cryptovalue, sizeof(cryptovalue),
+                   "7f853eef001b1bc5a1a5e67f5dfd0e90427596d4842f5b10a111e97c4073203c"
+                   "edf6b80a85145e5061acd29bc5a4e349b14f8557a7033e23b066b7ce2409d973"));
+
+  CHECK_STATUS(zip32_registered_cryptovalue((const uint8_t *)CONTEXT, strlen(CONTEXT), seed,
+                                            32, 1, NULL, 0, cryptovalue, sizeof(cryptovalue)),
+               ZIP32_STATUS_SUBPATH_EMPTY);
+  CHECK_STATUS(zip32_registered_cryptovalue((const uint8_t *)CONTEXT, strlen(CONTEXT), seed,
+                                            32, 1, subpath, 2, cryptovalue,
+                                            sizeof(cryptovalue) - 1),
+               ZIP32_STATUS_BUFFER_TOO_SMALL);
+
+  subpath[1].tag = LONG_TAG;
+  subpath[1].tag_len = sizeof(LONG_TAG);
+  CHECK_STATUS(zip32_registered_cryptovalue((const uint8_t *)CONTEXT, strlen(CONTEXT), seed,
+                                            32, 1, subpath, 2, cryptovalue,
+                                            sizeof(cryptovalue)),
+               ZIP32_STATUS_OK);
+
+  subpath[1].tag = NULL;
+  subpath[1].tag_len = 1;
+  CHECK_STATUS(zip32_registered_derive_key((const uint8_t *)CONTEXT, strlen(CONTEXT), seed, 32,
+                                           1, subpath, 2, key, sizeof(key)),
+               ZIP32_STATUS_NULL_POINTER);
+}
+
+static void test_diversifier_index(void) {
+  uint8_t di[ZIP32_DIVERSIFIER_INDEX_LEN];
+  uint8_t max[ZIP32_DIVERSIFIER_INDEX_LEN];
+  uint64_t value = 0;
+
+  CHECK_STATUS(zip32_diversifier_index_from_u64(258, di, sizeof(di)), ZIP32_STATUS_OK);
+  CHECK(equals_hex(di, sizeof(di), "0201000000000000000000"));
+  CHECK_STATUS(zip32_diversifier_index_increment(di, sizeof(di)), ZIP32_STATUS_OK);
+  CHECK_STATUS(zip32_diversifier_index_to_u64(di, sizeof(di), &value), ZIP32_STATUS_OK);
+  CHECK(value == 259);
+
+  CHECK_STATUS(zip32_diversifier_index_from_u64(UINT64_MAX, di, sizeof(di)), ZIP32_STATUS_OK);
+  CHECK_STATUS(zip32_diversifier_index_increment(di, sizeof(di)), ZIP32_STATUS_OK);
+  CHECK(equals_hex(di, sizeof(di), "0000000000000000010000"));
+  CHECK_STATUS(zip32_diversifier_index_to_u64(di, sizeof(di), &value), ZIP32_STATUS_OUT_OF_RANGE);
+
+  /* An overflowing increment leaves the index unchanged. */
+  memset(max, 0xff, sizeof(max));
+  CHECK_STATUS(zip32_diversifier_index_increment(max, sizeof(max)), ZIP32_STATUS_OUT_OF_RANGE);
+  CHECK(equals_hex(max, sizeof(max), "ffffffffffffffffffffff"));
+
+  CHECK_STATUS(zip32_diversifier_index_from_u64(1, di, sizeof(di) - 1),
+               ZIP32_STATUS_BUFFER_TOO_SMALL);
+  CHECK_STATUS(zip32_diversifier_index_to_u64(di, sizeof(di) + 1, &value),
+               ZIP32_STATUS_INVALID_LENGTH);
+  CHECK_STATUS(zip32_diversifier_index_to_u64(di, sizeof(di), NULL), ZIP32_STATUS_NULL_POINTER);
+}
+
+int main(void) {
+  uint8_t seed[253];
+  size_t i;
+  for (i = 0; i < sizeof(seed); i++) {
+    seed[i] = (uint8_t)i;
+  }
+
+  test_seed_fingerprint(seed);
+  test_arbitrary(seed);
+  test_registered(seed);
+  test_diversifier_index();
+
+  if (failures != 0) {
+    fprintf(stderr, "%d check(s) failed\n", failures);
+    return 1;
+  }
+  printf("all checks passed\n");
+  return 0;
+}
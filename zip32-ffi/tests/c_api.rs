@@ -0,0 +1,62 @@
+//! Builds and runs the C test program in `tests/c` against the static library.
+
+#![cfg(unix)]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn c_test_program() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let tmp_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+
+    // `cargo test` does not build the `staticlib` artifact, so build it here. A separate
+    // target directory is used because the enclosing `cargo` invocation holds the lock on
+    // the main one. The crate is cleaned first so that `rustc` runs and prints the native
+    // libraries that the static library must be linked with.
+    let target_dir = tmp_dir.join("c_api");
+    let cargo = |args: &[&str]| {
+        // The subcommand comes first and any arguments after `--` go to `rustc`, so the
+        // shared options are inserted between them.
+        let (subcommand, args) = args.split_first().expect("a cargo subcommand");
+        Command::new(env!("CARGO"))
+            .arg(subcommand)
+            .arg("--manifest-path")
+            .arg(manifest_dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .args(args)
+            .output()
+            .expect("failed to run cargo")
+    };
+    assert!(cargo(&["clean", "-p", "zip32-ffi"]).status.success());
+    let output = cargo(&["rustc", "--lib", "--", "--print", "native-static-libs"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    let native_libs = stderr
+        .lines()
+        .find_map(|line| line.split_once("native-static-libs: "))
+        .map(|(_, libs)| libs.split_whitespace().collect::<Vec<_>>())
+        .expect("rustc did not print the native static libraries");
+    let lib = target_dir.join("debug").join("libzip32_ffi.a");
+
+    let program = tmp_dir.join("test_zip32");
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(cc)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-I"])
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c/test_zip32.c"))
+        .arg(&lib)
+        .args(&native_libs)
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("a C compiler is required to run this test");
+    assert!(status.success(), "failed to compile the C test program");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,378 @@
+//! WebAssembly bindings for the [`zip32`] crate, for use from JavaScript via
+//! [`wasm-bindgen`].
+//!
+//! Seeds are copied into WebAssembly memory and zeroized as soon as they have been used.
+//! Derived keys and cryptovalues are returned as opaque objects that are zeroized when
+//! they are freed; their bytes are only copied out to JavaScript on request. Registered
+//! keys are also zeroized once they have been copied into these objects, but
+//! [`zip32::arbitrary::SecretKey`] cannot be zeroized, so ad-hoc keys are not.
+//!
+//! Paths are written as for [`zip32::path`], for example `m/32'/133'/0'`. Registered
+//! subpaths are relative to the root of the ZIP's subtree, and each element may carry a
+//! hex-encoded tag, as in `2':7461/3'`.
+//!
+//! [`wasm-bindgen`]: https://rustwasm.github.io/docs/wasm-bindgen/
+
+#![deny(missing_docs)]
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+use zip32::{
+    arbitrary, fingerprint, path,
+    registered::{self, DerivationError},
+    ChainCode,
+};
+
+/// An error thrown by the functions in this module, as a JavaScript `Error`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A seed was shorter than 32 bytes, or longer than 252 bytes.
+    InvalidSeed,
+    /// A string was not a valid seed fingerprint.
+    InvalidSeedFingerprint,
+    /// A string was not a valid derivation path.
+    InvalidPath(path::ParseError),
+    /// A path passed to ad-hoc derivation had a tag.
+    UnexpectedTag,
+    /// Key derivation failed, or its inputs were invalid.
+    Derivation(DerivationError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSeed => write!(f, "Seed must be between 32 and 252 bytes, inclusive."),
+            Error::InvalidSeedFingerprint => write!(f, "Invalid seed fingerprint."),
+            Error::InvalidPath(e) => write!(f, "Invalid path: {}", e),
+            Error::UnexpectedTag => write!(f, "Ad-hoc key derivation does not support tags."),
+            Error::Derivation(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for JsValue {
+    fn from(e: Error) -> Self {
+        JsError::new(&e.to_string()).into()
+    }
+}
+
+/// The fingerprint of a seed, as defined in [ZIP 32].
+///
+/// [ZIP 32]: https://zips.z.cash/zip-0032#seed-fingerprints
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeedFingerprint(fingerprint::SeedFingerprint);
+
+#[wasm_bindgen]
+impl SeedFingerprint {
+    /// Computes the fingerprint of the given seed, which is zeroized afterwards.
+    #[wasm_bindgen(js_name = fromSeed)]
+    pub fn from_seed(seed: Vec<u8>) -> Result<SeedFingerprint, Error> {
+        let seed = Zeroizing::new(seed);
+        fingerprint::SeedFingerprint::from_seed(&seed)
+            .map(SeedFingerprint)
+            .ok_or(Error::InvalidSeed)
+    }
+
+    /// Reconstructs a fingerprint from its 32-byte encoding.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<SeedFingerprint, Error> {
+        <[u8; 32]>::try_from(bytes)
+            .map(|bytes| SeedFingerprint(fingerprint::SeedFingerprint::from_bytes(bytes)))
+            .map_err(|_| Error::InvalidSeedFingerprint)
+    }
+
+    /// Parses a Bech32m-encoded fingerprint.
+    pub fn parse(encoded: &str) -> Result<SeedFingerprint, Error> {
+        encoded
+            .parse()
+            .map(SeedFingerprint)
+            .map_err(|_| Error::InvalidSeedFingerprint)
+    }
+
+    /// Returns the 32-byte encoding of this fingerprint.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    /// Returns the Bech32m encoding of this fingerprint.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn encode(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// A parsed derivation path, consisting of hardened child indices with optional tags.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path(path::Path);
+
+#[wasm_bindgen]
+impl Path {
+    /// Parses a derivation path, such as `m/32'/133'/0'`.
+    pub fn parse(s: &str) -> Result<Path, Error> {
+        s.parse().map(Path).map_err(Error::InvalidPath)
+    }
+
+    /// The number of elements in this path.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.elements().len()
+    }
+
+    /// Returns the child indices of this path's elements, including the hardened bit.
+    #[wasm_bindgen(js_name = childIndices)]
+    pub fn child_indices(&self) -> Vec<u32> {
+        self.0
+            .child_indices()
+            .iter()
+            .map(|index| index.index())
+            .collect()
+    }
+
+    /// Returns the tag of the element at the given position, or `undefined` if there is
+    /// no such element.
+    pub fn tag(&self, position: usize) -> Option<Vec<u8>> {
+        self.0
+            .elements()
+            .get(position)
+            .map(|elem| elem.tag().to_vec())
+    }
+
+    /// Returns the canonical form of this path.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn encode(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// An extended secret key, which is zeroized when it is freed.
+#[wasm_bindgen]
+pub struct ExtendedKey {
+    sk: Zeroizing<[u8; 32]>,
+    chain_code: Zeroizing<[u8; 32]>,
+}
+
+impl ExtendedKey {
+    fn new(sk: &[u8; 32], chain_code: &ChainCode) -> Self {
+        ExtendedKey {
+            sk: Zeroizing::new(*sk),
+            chain_code: Zeroizing::new(*chain_code.as_bytes()),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ExtendedKey {
+    /// Returns a copy of the 32-byte secret key.
+    #[wasm_bindgen(js_name = secretKey)]
+    pub fn secret_key(&self) -> Vec<u8> {
+        self.sk.to_vec()
+    }
+
+    /// Returns a copy of the 32-byte chain code.
+    #[wasm_bindgen(js_name = chainCode)]
+    pub fn chain_code(&self) -> Vec<u8> {
+        self.chain_code.to_vec()
+    }
+}
+
+/// A 64-byte registered cryptovalue, which is zeroized when it is freed.
+#[wasm_bindgen]
+pub struct Cryptovalue(Zeroizing<[u8; 64]>);
+
+#[wasm_bindgen]
+impl Cryptovalue {
+    /// Returns a copy of the cryptovalue's bytes.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+/// Derives an ad-hoc key at the given path from the given seed, which is zeroized
+/// afterwards, as specified in [ZIP 32: Ad-hoc key derivation (deprecated)][adhoc].
+///
+/// [adhoc]: https://zips.z.cash/zip-0032#specification-ad-hoc-key-derivation-deprecated
+#[wasm_bindgen(js_name = deriveArbitraryKey)]
+pub fn derive_arbitrary_key(
+    context_string: &[u8],
+    seed: Vec<u8>,
+    path: &str,
+) -> Result<ExtendedKey, Error> {
+    let seed = Zeroizing::new(seed);
+    let path = Path::parse(path)?.0;
+    if path.has_tags() {
+        return Err(Error::UnexpectedTag);
+    }
+    registered::check_master_inputs(context_string, &seed).map_err(Error::Derivation)?;
+
+    let key = arbitrary::SecretKey::from_path(context_string, &seed, &path.child_indices());
+    Ok(ExtendedKey::new(key.data(), key.chain_code()))
+}
+
+/// Derives a registered key at the given subpath of the subtree for the given ZIP, from
+/// the given seed, which is zeroized afterwards, as specified in
+/// [ZIP 32: Registered key derivation][regkd].
+///
+/// [regkd]: https://zips.z.cash/zip-0032#specification-registered-key-derivation
+#[wasm_bindgen(js_name = deriveRegisteredKey)]
+pub fn derive_registered_key(
+    context_string: &[u8],
+    seed: Vec<u8>,
+    zip_number: u16,
+    subpath: &str,
+) -> Result<ExtendedKey, Error> {
+    let seed = Zeroizing::new(seed);
+    let subpath = Path::parse(subpath)?.0;
+
+    let key =
+        registered::SecretKey::from_subpath(context_string, &seed, zip_number, &subpath.subpath())
+            .map(Zeroizing::new)
+            .map_err(Error::Derivation)?;
+    Ok(ExtendedKey::new(key.data(), key.chain_code()))
+}
+
+/// Derives a 64-byte registered cryptovalue at the given non-empty subpath of the subtree
+/// for the given ZIP, from the given seed, which is zeroized afterwards, as specified in
+/// [ZIP 32: Registered key derivation][regkd].
+///
+/// [regkd]: https://zips.z.cash/zip-0032#specification-registered-key-derivation
+#[wasm_bindgen(js_name = deriveRegisteredCryptovalue)]
+pub fn derive_registered_cryptovalue(
+    context_string: &[u8],
+    seed: Vec<u8>,
+    zip_number: u16,
+    subpath: &str,
+) -> Result<Cryptovalue, Error> {
+    let seed = Zeroizing::new(seed);
+    let subpath = Path::parse(subpath)?.0;
+
+    registered::cryptovalue_from_subpath(context_string, &seed, zip_number, &subpath.subpath())
+        .map(|cryptovalue| Cryptovalue(Zeroizing::new(cryptovalue)))
+        .map_err(Error::Derivation)
+}
+
+#[cfg(test)]
+mod tests {
+    use zip32::{path::ParseError, registered::DerivationError};
+
+    use super::{
+        derive_arbitrary_key, derive_registered_cryptovalue, derive_registered_key, Error, Path,
+        SeedFingerprint,
+    };
+
+    const CONTEXT: &[u8] = b"Zcash test vectors";
+    // "trans rights are human rights"
+    const TAG: &str = "7472616e7320726967687473206172652068756d616e20726967687473";
+
+    fn seed() -> Vec<u8> {
+        (0..32).collect()
+    }
+
+    #[test]
+    fn seed_fingerprint() {
+        let encoded = "zip32seedfp1mmlkqnpyvug0w9mdatgz4f6x7t7c65uf7urj24kuk42lm0j78t3sne2h0z";
+        let fp = SeedFingerprint::from_seed(seed()).unwrap();
+        assert_eq!(fp.encode(), encoded);
+        assert_eq!(
+            hex::encode(&fp.to_bytes()),
+            "deff604c246710f7176dead02aa746f2fd8d5389f7072556dcb555fdbe5e3ae3"
+        );
+        assert_eq!(SeedFingerprint::parse(encoded), Ok(fp));
+        assert_eq!(SeedFingerprint::from_bytes(&fp.to_bytes()), Ok(fp));
+
+        assert_eq!(
+            SeedFingerprint::from_seed(vec![0; 31]),
+            Err(Error::InvalidSeed)
+        );
+        assert_eq!(
+            SeedFingerprint::parse(&encoded[1..]),
+            Err(Error::InvalidSeedFingerprint)
+        );
+        assert_eq!(
+            SeedFingerprint::from_bytes(&[0; 31]),
+            Err(Error::InvalidSeedFingerprint)
+        );
+    }
+
+    #[test]
+    fn path() {
+        let path = Path::parse("32h/133'/0':00ff").unwrap();
+        assert_eq!(path.length(), 3);
+        assert_eq!(
+            path.child_indices(),
+            [0x8000_0020, 0x8000_0085, 0x8000_0000]
+        );
+        assert_eq!(path.tag(0), Some(vec![]));
+        assert_eq!(path.tag(2), Some(vec![0x00, 0xff]));
+        assert_eq!(path.tag(3), None);
+        assert_eq!(path.encode(), "m/32'/133'/0':00ff");
+
+        assert_eq!(
+            Path::parse("m/1'/2"),
+            Err(Error::InvalidPath(ParseError::NotHardened(1)))
+        );
+    }
+
+    #[test]
+    fn arbitrary_derivation() {
+        let key = derive_arbitrary_key(CONTEXT, seed(), "m/1'/2'/3'").unwrap();
+        assert_eq!(
+            hex::encode(&key.secret_key()),
+            "fc4b6e93b0e42f7a762ca0c6522ccd1045cab506b372452af7306c87389ab62c"
+        );
+        assert_eq!(
+            hex::encode(&key.chain_code()),
+            "e89bf2ed73f5e0887542e36793fac82c508ab5d99198578227b241fbac198429"
+        );
+
+        assert_eq!(
+            derive_arbitrary_key(CONTEXT, seed(), "m/1':00").err(),
+            Some(Error::UnexpectedTag)
+        );
+        assert_eq!(
+            derive_arbitrary_key(b"", seed(), "m/1'").err(),
+            Some(Error::Derivation(DerivationError::ContextStringInvalid))
+        );
+        assert_eq!(
+            derive_arbitrary_key(CONTEXT, vec![0; 253], "m/1'").err(),
+            Some(Error::Derivation(DerivationError::SeedInvalid))
+        );
+    }
+
+    #[test]
+    fn registered_derivation() {
+        let key = derive_registered_key(CONTEXT, seed(), 1, &format!("2':{}", TAG)).unwrap();
+        assert_eq!(
+            hex::encode(&key.secret_key()),
+            "02dc25cc40310eed08b028e07fae9adbee2fbe56a4694def0401e656dfae0211"
+        );
+        assert_eq!(
+            hex::encode(&key.chain_code()),
+            "d8f9d8a1f81d1b5d5506b5ff942d2ff3dae7a63f57d6b8c7fbe58149823cc6ec"
+        );
+
+        let cryptovalue =
+            derive_registered_cryptovalue(CONTEXT, seed(), 1, &format!("2':{}/3'", TAG)).unwrap();
+        assert_eq!(
+            hex::encode(&cryptovalue.to_bytes()),
+            "7f853eef001b1bc5a1a5e67f5dfd0e90427596d4842f5b10a111e97c4073203c\
+             edf6b80a85145e5061acd29bc5a4e349b14f8557a7033e23b066b7ce2409d973"
+        );
+
+        assert_eq!(
+            derive_registered_cryptovalue(CONTEXT, seed(), 1, "m").err(),
+            Some(Error::Derivation(DerivationError::SubpathEmpty))
+        );
+        assert_eq!(
+            derive_registered_key(CONTEXT, vec![0; 31], 1, "").err(),
+            Some(Error::Derivation(DerivationError::SeedInvalid))
+        );
+    }
+}
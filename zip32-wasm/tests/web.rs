@@ -0,0 +1,32 @@
+//! Tests of the JavaScript bindings, run under Node.js with `wasm-pack test --node`.
+
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+use zip32_wasm::{derive_registered_cryptovalue, Error, SeedFingerprint};
+
+#[wasm_bindgen_test]
+fn seed_fingerprint_round_trip() {
+    let seed = (0..32).collect::<Vec<u8>>();
+    let fp = SeedFingerprint::from_seed(seed).unwrap();
+    assert_eq!(
+        fp.encode(),
+        "zip32seedfp1mmlkqnpyvug0w9mdatgz4f6x7t7c65uf7urj24kuk42lm0j78t3sne2h0z"
+    );
+    assert_eq!(SeedFingerprint::parse(&fp.encode()).unwrap(), fp);
+}
+
+#[wasm_bindgen_test]
+fn cryptovalue() {
+    let seed = (0..32).collect::<Vec<u8>>();
+    let cryptovalue =
+        derive_registered_cryptovalue(b"Zcash test vectors", seed, 1, "2'/3'").unwrap();
+    assert_eq!(cryptovalue.to_bytes().len(), 64);
+}
+
+#[wasm_bindgen_test]
+fn errors_become_js_errors() {
+    let error = JsValue::from(Error::InvalidSeed);
+    assert!(error.is_object());
+}